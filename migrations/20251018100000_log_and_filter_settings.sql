@@ -0,0 +1,12 @@
+CREATE TABLE log_settings (
+    guild_id BIGINT PRIMARY KEY REFERENCES guilds(guild_id) ON DELETE CASCADE,
+    join_logs_channel_id BIGINT,
+    blacklist_logs_channel_id BIGINT,
+    voice_logs_channel_id BIGINT
+);
+
+CREATE TABLE filter_settings (
+    guild_id BIGINT PRIMARY KEY REFERENCES guilds(guild_id) ON DELETE CASCADE,
+    is_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    bypass_roles BIGINT[] NOT NULL DEFAULT '{}'
+);
@@ -0,0 +1 @@
+ALTER TABLE guilds ADD COLUMN stock_prefix_disabled BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+-- emote_id is our own id (discord_id is the Discord one), give it a generator so rows can be
+-- inserted without picking ids by hand.
+CREATE SEQUENCE emotes_emote_id_seq OWNED BY emotes.emote_id;
+SELECT setval('emotes_emote_id_seq', COALESCE((SELECT MAX(emote_id) FROM emotes), 0) + 1, false);
+ALTER TABLE emotes ALTER COLUMN emote_id SET DEFAULT nextval('emotes_emote_id_seq');
@@ -0,0 +1,6 @@
+-- Snowflakes don't fit in an INT, these columns could never hold a real channel or guild id.
+ALTER TABLE executed_commands ALTER COLUMN channel_id TYPE BIGINT;
+ALTER TABLE executed_commands ALTER COLUMN guild_id TYPE BIGINT;
+ALTER TABLE role_snapshots ALTER COLUMN guild_id TYPE BIGINT;
+
+CREATE INDEX executed_commands_command_idx ON executed_commands (command, executed_at DESC);
@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN bot_ban_reason TEXT;
+ALTER TABLE guilds ADD COLUMN ban_reason TEXT;
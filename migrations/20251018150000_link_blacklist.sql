@@ -0,0 +1,1409 @@
+-- The link blacklist used to be compiled in, now it's global entries (guild_id NULL) plus per guild
+-- additions and exceptions that can be changed without a rebuild.
+CREATE TABLE link_blacklist (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT REFERENCES guilds(guild_id),
+    -- a case insensitive regex matched against the links in a message.
+    pattern TEXT NOT NULL,
+    -- exceptions let links through in their guild even if another entry matches them.
+    is_exception BOOLEAN NOT NULL DEFAULT FALSE,
+    added_by BIGINT,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX link_blacklist_global_pattern_idx ON link_blacklist (pattern)
+    WHERE guild_id IS NULL;
+CREATE UNIQUE INDEX link_blacklist_guild_pattern_idx ON link_blacklist (guild_id, pattern)
+    WHERE guild_id IS NOT NULL;
+
+-- the entries from regex_link_filters.txt.
+INSERT INTO link_blacklist (pattern) VALUES
+    ('https://dlscord-nitro\.info'),
+    ('https://dlscord\.org'),
+    ('https://dlscordsteam\.com'),
+    ('https://nitro-drop\.com'),
+    ('https://steamcomminuty\.com'),
+    ('https://steamcomminutty\.ru'),
+    ('https://discord\.giveawey\.com'),
+    ('https://steamsnitro\.ru'),
+    ('http://discorcl-gift\.ru'),
+    ('https://dlscorldnitro\.store'),
+    ('https://steamdiscord\.com'),
+    ('https://dlscord-app\.com'),
+    ('https://discords-nitro\.xyz'),
+    ('https://discord-drop\.xyz'),
+    ('https://discord-app\.net'),
+    ('http://gift-g2\.ru'),
+    ('https://discord-give\.com'),
+    ('http://discocrd-nitro\.com'),
+    ('https://steancornminuty\.com'),
+    ('https://dlscordnitro\.com'),
+    ('https://dlscord\.net'),
+    ('https://discordrgift\.com'),
+    ('https://gamex\.codes'),
+    ('https://discordnitrocodes\.blogspot\.com'),
+    ('http://shared-tools\.xyz'),
+    ('https://techgenerator\.xyz'),
+    ('https://rbuxfree\.com'),
+    ('https://genrates\.xyz'),
+    ('https://psp-haxors\.com'),
+    ('http://arcades\.tech'),
+    ('https://discord\.freehlp\.com'),
+    ('https://sehotgame\.xyz'),
+    ('https://www\.appninjas\.xyz'),
+    ('https://discorid\.gift'),
+    ('https://discorcll\.com'),
+    ('https://dlscord-app\.info'),
+    ('https://discord-app\.uk'),
+    ('https://disordapp\.codes'),
+    ('https://discordsgift\.com'),
+    ('https://discordc\.gift'),
+    ('https://dliscord\.com'),
+    ('http://discorl\.com'),
+    ('https://discorde\.gift'),
+    ('https://discordgift\.site'),
+    ('https://steamcomnmuituy\.com'),
+    ('https://dicsord\.net'),
+    ('https://discord-nitro\.link'),
+    ('https://discorcl\.shop'),
+    ('https://discorcl\.click'),
+    ('https://discodnitro\.info'),
+    ('https://discord-airdrop\.com'),
+    ('https://discordapp\.click'),
+    ('https://steamcomunity-nitro-free\.ru'),
+    ('https://steamdlscord\.com'),
+    ('https://discrodapp\.ru'),
+    ('https://discorcd\.gifts'),
+    ('https://dicsordgift\.com'),
+    ('https://discocrdapp\.com'),
+    ('https://discorclapp\.com'),
+    ('https://discord-app\.me'),
+    ('https://discord-give\.org'),
+    ('https://discords-gift\.com'),
+    ('https://discordgift\.app'),
+    ('https://discord-cpp\.com'),
+    ('https://dlscord\.net'),
+    ('https://discordd\.gift'),
+    ('https://nitro-app-store\.com'),
+    ('https://discord-gifte\.com'),
+    ('https://discordj\.gift'),
+    ('101nitro\.com'),
+    ('boost-discord\.com'),
+    ('boostnitro\.com'),
+    ('cllscordapp\.fun'),
+    ('cpp-discord\.com'),
+    ('dicsord-give\.com'),
+    ('dicsord-nitro\.com'),
+    ('dicsord\.net'),
+    ('diiscord\.gift'),
+    ('dirscod\.com'),
+    ('discapp\.info'),
+    ('discoapps\.club'),
+    ('discocrd-nitro\.com'),
+    ('discocrd\.com'),
+    ('discocrd\.gift'),
+    ('discocrd\.gifts'),
+    ('discocrdapp\.com'),
+    ('discod\.art'),
+    ('discod\.fun'),
+    ('discod\.gift'),
+    ('discod\.info'),
+    ('discorb\.ru'),
+    ('discorcd\.gifts'),
+    ('discorcl-app\.com'),
+    ('discorcl\.click'),
+    ('discorcl\.club'),
+    ('discorcl\.gift'),
+    ('discorcl\.link'),
+    ('discorclapp\.com'),
+    ('discorclapp\.fun'),
+    ('discord-air\.xyz'),
+    ('discord-airdrop\.com'),
+    ('discord-app\.me'),
+    ('discord-app\.net'),
+    ('discord-app\.uk'),
+    ('discord-claim\.com'),
+    ('discord-cpp\.com'),
+    ('discord-drop\.info'),
+    ('discord-free\.com'),
+    ('discord-game\.com'),
+    ('discord-gifts\.com'),
+    ('discord-give\.com'),
+    ('discord-give\.org'),
+    ('discord-glft\.com'),
+    ('discord-help\.com'),
+    ('discord-nitro\.click'),
+    ('discord-nitro\.link'),
+    ('discord-nitro\.pro'),
+    ('discord-nitro\.su'),
+    ('discord-promo\.com'),
+    ('discord-steam\.com'),
+    ('discord-steam\.ru'),
+    ('discord\.foundation'),
+    ('discord\.givaewey\.com'),
+    ('discord\.giveaweys\.com'),
+    ('discord\.moscow'),
+    ('discordapp\.click'),
+    ('discordapp\.gg'),
+    ('discordapp\.info'),
+    ('discordapp\.one'),
+    ('discordcommunlty\.com'),
+    ('discordd\.gift'),
+    ('discordevents\.com'),
+    ('discordf\.gift'),
+    ('discordfrnitro\.site'),
+    ('discordgift\.app'),
+    ('discordgift\.ru\.com'),
+    ('discordgift\.site'),
+    ('discordgifts\.ru'),
+    ('discordhalloween\.co\.uk'),
+    ('discordhalloween\.com'),
+    ('discordhalloween\.uk'),
+    ('discordnitro\.click'),
+    ('discordnitrogift\.ru'),
+    ('discordrgift\.com'),
+    ('discords-nitro\.xyz'),
+    ('discordsteams\.com'),
+    ('discorid\.gift'),
+    ('discorl\.com'),
+    ('discrod-gift\.com'),
+    ('discrod\.gifts'),
+    ('discrodapp\.ru'),
+    ('discrodnitro\.org'),
+    ('discrodsteam\.ru'),
+    ('diskord\.ru\.com'),
+    ('disord\.gifts'),
+    ('disordgift\.codes'),
+    ('dissord\.ru'),
+    ('dizcord\.gift'),
+    ('dliscord\.us'),
+    ('dlscard\.ru'),
+    ('dlscocrdapp\.com'),
+    ('dlscorcl\.info'),
+    ('dlscord-app\.com'),
+    ('dlscord-app\.info'),
+    ('dlscord-nitro\.click'),
+    ('dlscord-nitro\.link'),
+    ('dlscord\.app'),
+    ('dlscord\.net'),
+    ('dlscord\.one'),
+    ('dlscord\.org'),
+    ('dlscord\.wiki'),
+    ('dlscord\.work'),
+    ('dlscord\.world'),
+    ('dlscordapp\.info'),
+    ('dlscordgived\.xyz'),
+    ('dlscordnitro\.com'),
+    ('driscord\.ru'),
+    ('driscord\.ru\.com'),
+    ('dsicord\.gift'),
+    ('free-nitlross\.ru'),
+    ('free-nitroi\.ru'),
+    ('freenitros\.ru'),
+    ('gave-nitro\.com'),
+    ('get-nitro\.net'),
+    ('giftdiscord\.info'),
+    ('giiveaway-discord\.com'),
+    ('glets-nitro\.com'),
+    ('glft-discord\.com'),
+    ('hallowen-nitro\.com'),
+    ('nitro-app-store\.com'),
+    ('nitroappstore\.com'),
+    ('official-nitro\.fun'),
+    ('steam-discords\.com'),
+    ('steam-dlscord\.com'),
+    ('steamcommunity-nitro\.ru'),
+    ('steamdiscord\.com'),
+    ('steamdlscords\.com'),
+    ('steamsdiscord\.com'),
+    ('steancommunity\.link/category/discord'),
+    ('discordapp\.co/'),
+    ('nitro-gift\.space'),
+    ('dlscord-nitro\.space'),
+    ('dilscord\.com'),
+    ('dlscordapps\.com'),
+    ('discocrd-gift'),
+    ('bit\.ly'),
+    ('freenlitro'),
+    ('discord\.gift'),
+    ('disccord-nitro'),
+    ('discord-nittro'),
+    ('dlscord-store'),
+    ('discorcl'),
+    ('nitro'),
+    ('dlscord'),
+    ('discorc'),
+    ('discoc'),
+    ('discr'),
+    ('dlsccord'),
+    ('discort'),
+    ('nittro'),
+    ('mapper_2\.exe'),
+    ('discorde'),
+    ('diisccord'),
+    ('dlscro'),
+    ('dlisoc'),
+    ('dlsco'),
+    ('discro'),
+    ('discc'),
+    ('discorr'),
+    ('-discord\.'),
+    ('discords\.'),
+    ('discrode'),
+    ('disord'),
+    ('steamdiscord'),
+    ('discord-gift'),
+    ('discord-nitre'),
+    ('dilsco'),
+    ('steamdiscor'),
+    ('discorda\.'),
+    ('dfscord'),
+    ('discoord'),
+    ('discoqd'),
+    ('newyear-discord'),
+    ('discordsteam'),
+    ('steamldiscord'),
+    ('disordsnltro'),
+    ('discgrd'),
+    ('gifte-discorde'),
+    ('dicsordgive'),
+    ('discord-web'),
+    ('discord-to'),
+    ('discojd'),
+    ('discorb'),
+    ('discqrd'),
+    ('discqr'),
+    ('disckord'),
+    ('discrde'),
+    ('steamcomn'),
+    ('dscord'),
+    ('disgord'),
+    ('discorz\.'),
+    ('steamcommunitvs'),
+    ('disocr'),
+    ('6J0TmxJwBmE'),
+    ('e-giftpremium'),
+    ('coldexbit'),
+    ('dlisco'),
+    ('transfer\.sh'),
+    ('diskord'),
+    ('dilscro'),
+    ('discerd'),
+    ('disczr'),
+    ('dicszr'),
+    ('currency-app'),
+    ('axieinfinity'),
+    ('nltro'),
+    ('disceor'),
+    ('discords-go'),
+    ('m5_game_v3'),
+    ('m5_game_v'),
+    ('steamcommunitee'),
+    ('SquizzysGameInstaller'),
+    ('cryptonix'),
+    ('gitvhub'),
+    ('gitxhub'),
+    ('gitqhub'),
+    ('gitwhub'),
+    ('gitfhub'),
+    ('gitphub'),
+    ('gitbhub'),
+    ('gitjhub'),
+    ('gitlhub'),
+    ('gituhub'),
+    ('gityhub'),
+    ('gitahub'),
+    ('gitrhub'),
+    ('gitshub'),
+    ('gitthub'),
+    ('gitghub'),
+    ('gitmhub'),
+    ('gitnhub'),
+    ('gitehub'),
+    ('gitihub'),
+    ('gitohub'),
+    ('gitzhub'),
+    ('gitchub'),
+    ('gitdhub'),
+    ('gitkhub'),
+    ('githhub'),
+    ('SkyBlade\.rar'),
+    ('flyblade\.rar'),
+    ('Ngame%20v3\.rar'),
+    ('Ngame v3\.rar'),
+    ('Ngame-v3\.rar'),
+    ('Ngame_v3\.rar'),
+    ('Ngame%20v2\.4\.rar'),
+    ('Ngame v2\.4\.rar'),
+    ('Ngame-v2\.4\.rar'),
+    ('Ngame_v2\.4\.rar'),
+    ('Ngame%20v'),
+    ('Ngame_v'),
+    ('cubeproject\.live'),
+    ('opaldownloads'),
+    ('gibthub'),
+    ('boredapeyachtclub\.website'),
+    ('preimum'),
+    ('UnbodGame'),
+    ('yobit'),
+    ('SicmW'),
+    ('futureswap\.'),
+    ('futureswap\.space'),
+    ('futureswap'),
+    ('nltroi'),
+    ('OpenSea_NFTBot_Universal_v2\.57\.rar'),
+    ('OpenSea_NFTBot'),
+    ('opensea nftbot'),
+    ('openseanftbot'),
+    ('gidthub'),
+    ('premium-ds'),
+    ('discrd'),
+    ('steamcommunety'),
+    ('disrcord'),
+    ('discordj'),
+    ('discrrd'),
+    ('discordl'),
+    ('get-3month'),
+    ('dlscorde'),
+    ('dscd-nitro'),
+    ('steamcanmunity'),
+    ('3month-premium'),
+    ('egiftdiscord'),
+    ('egiftfromsteam'),
+    ('steamcommunltly'),
+    ('disrcod'),
+    ('airdropi'),
+    ('githvub'),
+    ('ee/game/raw/main'),
+    ('dlsscord'),
+    ('djscord'),
+    ('j1_ockFmSGE'),
+    ('borisfxcrack'),
+    ('dicsord'),
+    ('discord-add'),
+    ('freenitro'),
+    ('dsc-glft'),
+    ('1month-premium'),
+    ('EfkWYT33Yr'),
+    ('nitrogifc'),
+    ('nitrogitt'),
+    ('dscordapb-glft'),
+    ('dscordapb'),
+    ('discord-start'),
+    ('discordnltro'),
+    ('nitross'),
+    ('dixcord'),
+    ('dizcord'),
+    ('diqcord'),
+    ('diwcord'),
+    ('difcord'),
+    ('dipcord'),
+    ('dibcord'),
+    ('dijcord'),
+    ('dilcord'),
+    ('diucord'),
+    ('diycord'),
+    ('diacord'),
+    ('dircord'),
+    ('ditcord'),
+    ('digcord'),
+    ('dimcord'),
+    ('dincord'),
+    ('diecord'),
+    ('diicord'),
+    ('diocord'),
+    ('diccord'),
+    ('didcord'),
+    ('divcord'),
+    ('dikcord'),
+    ('dihcord'),
+    ('stepn-gps'),
+    ('rafffle'),
+    ('famousfoxes'),
+    ('pandaq'),
+    ('dlcsord'),
+    ('dicord'),
+    ('gg/icecreams'),
+    ('hypesquad'),
+    ('u\.to'),
+    ('dilscird'),
+    ('dilscord'),
+    ('rustcasec'),
+    ('clck\.ru'),
+    ('exhub'),
+    ('cryptonftgiveaway'),
+    ('cryptogiveaway'),
+    ('cutt\.us'),
+    ('web-telegrarn'),
+    ('telegrarn'),
+    ('csgo2world'),
+    ('csgo2sourcses\.com'),
+    ('://rb\.gy'),
+    ('://myglfts'),
+    ('://tinyurl'),
+    ('://t\.me'),
+    ('://0rz\.tw'),
+    ('://1-url\.net'),
+    ('://126\.am'),
+    ('://1link\.in'),
+    ('://1tk\.us'),
+    ('://1un\.fr'),
+    ('://1url\.com'),
+    ('://1url\.cz'),
+    ('://1wb2\.net'),
+    ('://2\.gp'),
+    ('://2\.ht'),
+    ('://23o\.net'),
+    ('://2ad\.in'),
+    ('://2big\.at'),
+    ('://2doc\.net'),
+    ('://2fear\.com'),
+    ('://2pl\.us'),
+    ('://2tu\.us'),
+    ('://2ty\.in'),
+    ('://2u\.xf\.cz'),
+    ('://2ya\.com'),
+    ('://3ra\.be'),
+    ('://3x\.si'),
+    ('://4i\.ae'),
+    ('://4url\.cc'),
+    ('://4view\.me'),
+    ('://5em\.cz'),
+    ('://5url\.net'),
+    ('://5z8\.info'),
+    ('://6fr\.ru'),
+    ('://6g6\.eu'),
+    ('://6url\.com'),
+    ('://7\.ly'),
+    ('://76\.gd'),
+    ('://77\.ai'),
+    ('://7fth\.cc'),
+    ('://7li\.in'),
+    ('://7vd\.cn'),
+    ('://8u\.cz'),
+    ('://944\.la'),
+    ('://98\.to'),
+    ('://9m\.no'),
+    ('://9qr\.de'),
+    ('://AltURL\.com'),
+    ('://BudURL\.com'),
+    ('://Buff\.ly'),
+    ('://BurnURL\.com'),
+    ('://C-O\.IN'),
+    ('://ClickMeter\.com'),
+    ('://DecentURL\.com'),
+    ('://DigBig\.com'),
+    ('://Digg\.com'),
+    ('://DwarfURL\.com'),
+    ('://EasyURI\.com'),
+    ('://EasyURL\.net'),
+    ('://EsyURL\.com'),
+    ('://Fhurl\.com'),
+    ('://Fly2\.ws'),
+    ('://GoWat\.ch'),
+    ('://Hurl\.it'),
+    ('://IsCool\.net'),
+    ('://Just\.as'),
+    ('://L9\.fr'),
+    ('://Lvvk\.com'),
+    ('://MyURL\.in'),
+    ('://PiURL\.com'),
+    ('://Profile\.to'),
+    ('://QLNK\.net'),
+    ('://Quip-Art\.com'),
+    ('://RedirX\.com'),
+    ('://Sharein\.com'),
+    ('://ShortLinks\.co\.uk'),
+    ('://Shrinkify\.com'),
+    ('://SimURL\.com'),
+    ('://StartURL\.com'),
+    ('://TightURL\.com'),
+    ('://Tnij\.org'),
+    ('://To8\.cc'),
+    ('://TraceURL\.com'),
+    ('://URL\.ie'),
+    ('://URLHawk\.com'),
+    ('://WapURL\.co\.uk'),
+    ('://XeeURL\.com'),
+    ('://Yep\.it'),
+    ('://a\.co'),
+    ('://a\.gg'),
+    ('://a\.nf'),
+    ('://a0\.fr'),
+    ('://a2a\.me'),
+    ('://abbr\.sk'),
+    ('://abbrr\.com'),
+    ('://ad-med\.cz'),
+    ('://ad5\.eu'),
+    ('://ad7\.biz'),
+    ('://adb\.ug'),
+    ('://adf\.ly'),
+    ('://adfa\.st'),
+    ('://adfly\.fr'),
+    ('://adfoc\.us'),
+    ('://adjix\.com'),
+    ('://adli\.pw'),
+    ('://admy\.link'),
+    ('://adv\.li'),
+    ('://ajn\.me'),
+    ('://aka\.gr'),
+    ('://al\.ly'),
+    ('://alil\.in'),
+    ('://any\.gs'),
+    ('://aqva\.pl'),
+    ('://ares\.tl'),
+    ('://asso\.in'),
+    ('://atu\.ca'),
+    ('://au\.ms'),
+    ('://ayt\.fr'),
+    ('://azali\.fr'),
+    ('://b00\.fr'),
+    ('://b23\.ru'),
+    ('://b54\.in'),
+    ('://bacn\.me'),
+    ('://baid\.us'),
+    ('://bc\.vc'),
+    ('://bee4\.biz'),
+    ('://bim\.im'),
+    ('://bit\.do'),
+    ('://bitly\.com'),
+    ('://bitw\.in'),
+    ('://bkite\.com'),
+    ('://blap\.net'),
+    ('://ble\.pl'),
+    ('://blip\.tv'),
+    ('://bloat\.me'),
+    ('://boi\.re'),
+    ('://bote\.me'),
+    ('://bougn\.at'),
+    ('://br4\.in'),
+    ('://brk\.to'),
+    ('://brzu\.net'),
+    ('://budurl\.com'),
+    ('://buk\.me'),
+    ('://bul\.lu'),
+    ('://bxl\.me'),
+    ('://bzh\.me'),
+    ('://cachor\.ro'),
+    ('://captur\.in'),
+    ('://catchylink\.com'),
+    ('://cbs\.so'),
+    ('://cbug\.cc'),
+    ('://cc\.cc'),
+    ('://ccj\.im'),
+    ('://cf\.ly'),
+    ('://cf2\.me'),
+    ('://cf6\.co'),
+    ('://chilp\.it'),
+    ('://cjb\.net'),
+    ('://cli\.gs'),
+    ('://clikk\.in'),
+    ('://cn86\.org'),
+    ('://coinurl\.com'),
+    ('://cort\.as'),
+    ('://couic\.fr'),
+    ('://cr\.tl'),
+    ('://cudder\.it'),
+    ('://cur\.lv'),
+    ('://curl\.im'),
+    ('://cut\.pe'),
+    ('://cut\.sk'),
+    ('://cutt\.eu'),
+    ('://cutu\.me'),
+    ('://cuturl\.com'),
+    ('://cybr\.fr'),
+    ('://cyonix\.to'),
+    ('://d75\.eu'),
+    ('://daa\.pl'),
+    ('://dai\.ly'),
+    ('://db\.tt'),
+    ('://dd\.ma'),
+    ('://ddp\.net'),
+    ('://decenturl\.com'),
+    ('://dfl8\.me'),
+    ('://dft\.ba'),
+    ('://doiop\.com'),
+    ('://dolp\.cc'),
+    ('://dopice\.sk'),
+    ('://droid\.ws'),
+    ('://dv\.gd'),
+    ('://dy\.fi'),
+    ('://dyo\.gs'),
+    ('://e37\.eu'),
+    ('://ecra\.se'),
+    ('://eepurl\.com'),
+    ('://ely\.re'),
+    ('://emojilink\.me'),
+    ('://erax\.cz'),
+    ('://erw\.cz'),
+    ('://ewerl\.com'),
+    ('://ex9\.co'),
+    ('://ezurl\.cc'),
+    ('://fa\.b'),
+    ('://ff\.im'),
+    ('://fff\.re'),
+    ('://fff\.to'),
+    ('://fff\.wf'),
+    ('://filz\.fr'),
+    ('://fire\.to'),
+    ('://firsturl\.de'),
+    ('://flic\.kr'),
+    ('://fly2\.ws'),
+    ('://fnk\.es'),
+    ('://foe\.hn'),
+    ('://folu\.me'),
+    ('://fon\.gs'),
+    ('://freze\.it'),
+    ('://fur\.ly'),
+    ('://fwd4\.me'),
+    ('://g00\.me'),
+    ('://gg\.gg'),
+    ('://git\.io'),
+    ('://gl\.am'),
+    ('://go\.9nl\.com'),
+    ('://go2\.me'),
+    ('://go2cut\.com'),
+    ('://goo\.gl'),
+    ('://goo\.lu'),
+    ('://good\.ly'),
+    ('://goshrink\.com'),
+    ('://grem\.io'),
+    ('://gri\.ms'),
+    ('://guiama\.is'),
+    ('://gurl\.es'),
+    ('://hadej\.co'),
+    ('://hec\.su'),
+    ('://hellotxt\.com'),
+    ('://hex\.io'),
+    ('://hide\.my'),
+    ('://hjkl\.fr'),
+    ('://hops\.me'),
+    ('://hover\.com'),
+    ('://href\.in'),
+    ('://href\.li'),
+    ('://ht\.ly'),
+    ('://htxt\.it'),
+    ('://hugeurl\.com'),
+    ('://hurl\.me'),
+    ('://hurl\.ws'),
+    ('://i-2\.co'),
+    ('://i99\.cz'),
+    ('://icanhaz\.com'),
+    ('://icit\.fr'),
+    ('://ick\.li'),
+    ('://icks\.ro'),
+    ('://idek\.net'),
+    ('://iiiii\.in'),
+    ('://iky\.fr'),
+    ('://ilix\.in'),
+    ('://info\.ms'),
+    ('://inreply\.to'),
+    ('://is\.gd'),
+    ('://isra\.li'),
+    ('://iterasi\.net'),
+    ('://itm\.im'),
+    ('://ity\.im'),
+    ('://ix\.sk'),
+    ('://j\.gs'),
+    ('://j\.mp'),
+    ('://jdem\.cz'),
+    ('://jieb\.be'),
+    ('://jijr\.com'),
+    ('://jmp2\.net'),
+    ('://jp22\.net'),
+    ('://jqw\.de'),
+    ('://kask\.us'),
+    ('://kd2\.org'),
+    ('://kfd\.pl'),
+    ('://kissa\.be'),
+    ('://kl\.am'),
+    ('://klck\.me'),
+    ('://korta\.nu'),
+    ('://kr3w\.de'),
+    ('://krat\.si'),
+    ('://kratsi\.cz'),
+    ('://krod\.cz'),
+    ('://krunchd\.com'),
+    ('://kuc\.cz'),
+    ('://kutt\.it'),
+    ('://kxb\.me'),
+    ('://l-k\.be'),
+    ('://l\.gg'),
+    ('://lc-s\.co'),
+    ('://lc\.cx'),
+    ('://lcut\.in'),
+    ('://letop10\.'),
+    ('://libero\.it'),
+    ('://lick\.my'),
+    ('://lien\.li'),
+    ('://lien\.pl'),
+    ('://liip\.to'),
+    ('://liltext\.com'),
+    ('://lin\.cr'),
+    ('://lin\.io'),
+    ('://linkbee\.com'),
+    ('://linkbun\.ch'),
+    ('://linkn\.co'),
+    ('://liurl\.cn'),
+    ('://llu\.ch'),
+    ('://ln-s\.net'),
+    ('://ln-s\.ru'),
+    ('://lnk\.co'),
+    ('://lnk\.gd'),
+    ('://lnk\.in'),
+    ('://lnk\.ly'),
+    ('://lnk\.sk'),
+    ('://lnkd\.in'),
+    ('://lnked\.in'),
+    ('://lnks\.fr'),
+    ('://lnky\.fr'),
+    ('://lnp\.sn'),
+    ('://loopt\.us'),
+    ('://lp25\.fr'),
+    ('://lru\.jp'),
+    ('://lt\.tl'),
+    ('://lurl\.no'),
+    ('://lynk\.my'),
+    ('://m1p\.fr'),
+    ('://m3mi\.com'),
+    ('://make\.my'),
+    ('://mcaf\.ee'),
+    ('://mdl29\.net'),
+    ('://metamark\.net'),
+    ('://mic\.fr'),
+    ('://migre\.me'),
+    ('://minilien\.com'),
+    ('://miniurl\.com'),
+    ('://minu\.me'),
+    ('://minurl\.fr'),
+    ('://moourl\.com'),
+    ('://more\.sh'),
+    ('://mut\.lu'),
+    ('://myurl\.in'),
+    ('://ne1\.net'),
+    ('://net\.ms'),
+    ('://net46\.net'),
+    ('://nicou\.ch'),
+    ('://nig\.gr'),
+    ('://niny\.io'),
+    ('://njx\.me'),
+    ('://nn\.nf'),
+    ('://notlong\.com'),
+    ('://nov\.io'),
+    ('://nq\.st'),
+    ('://nsfw\.in'),
+    ('://nxy\.in'),
+    ('://o-x\.fr'),
+    ('://okok\.fr'),
+    ('://om\.ly'),
+    ('://ou\.af'),
+    ('://ou\.gd'),
+    ('://oua\.be'),
+    ('://ouo\.io'),
+    ('://ow\.ly'),
+    ('://p\.pw'),
+    ('://para\.pt'),
+    ('://parky\.tv'),
+    ('://past\.is'),
+    ('://pd\.am'),
+    ('://pdh\.co'),
+    ('://ph\.dog'),
+    ('://ph\.ly'),
+    ('://pic\.gd'),
+    ('://pich\.in'),
+    ('://pin\.st'),
+    ('://ping\.fm'),
+    ('://plots\.fr'),
+    ('://pm\.wu\.cz'),
+    ('://pnt\.me'),
+    ('://po\.st'),
+    ('://poprl\.com'),
+    ('://post\.ly'),
+    ('://posted\.at'),
+    ('://ppfr\.it'),
+    ('://ppst\.me'),
+    ('://ppt\.cc'),
+    ('://ppt\.li'),
+    ('://prejit\.cz'),
+    ('://ptab\.it'),
+    ('://ptm\.ro'),
+    ('://pw2\.ro'),
+    ('://py6\.ru'),
+    ('://q\.gs'),
+    ('://qbn\.ru'),
+    ('://qicute\.com'),
+    ('://qqc\.co'),
+    ('://qr\.net'),
+    ('://qrtag\.fr'),
+    ('://qxp\.cz'),
+    ('://qxp\.sk'),
+    ('://rb6\.co'),
+    ('://rb6\.me'),
+    ('://rcknr\.io'),
+    ('://rdz\.me'),
+    ('://redir\.ec'),
+    ('://redir\.fr'),
+    ('://redu\.it'),
+    ('://ref\.so'),
+    ('://reise\.lc'),
+    ('://relink\.fr'),
+    ('://ri\.ms'),
+    ('://rickroll\.it'),
+    ('://riz\.cz'),
+    ('://riz\.gd'),
+    ('://rod\.gs'),
+    ('://roflc\.at'),
+    ('://rsmonkey\.com'),
+    ('://rt\.se'),
+    ('://rt\.tc'),
+    ('://ru\.ly'),
+    ('://rubyurl\.com'),
+    ('://s-url\.fr'),
+    ('://s\.id'),
+    ('://s7y\.us'),
+    ('://safe\.mn'),
+    ('://sagyap\.tk'),
+    ('://sdu\.sk'),
+    ('://seeme\.at'),
+    ('://segue\.se'),
+    ('://sh\.st'),
+    ('://shar\.as'),
+    ('://sharetabs\.com'),
+    ('://shorl\.com'),
+    ('://short\.cc'),
+    ('://short\.ie'),
+    ('://short\.nr'),
+    ('://short\.pk'),
+    ('://short\.to'),
+    ('://shorte\.st'),
+    ('://shortna\.me'),
+    ('://shorturl\.com'),
+    ('://shoturl\.us'),
+    ('://shrinkee\.com'),
+    ('://shrinkster\.com'),
+    ('://shrinkurl\.in'),
+    ('://shrt\.in'),
+    ('://shrt\.st'),
+    ('://shrtco\.de'),
+    ('://shrten\.com'),
+    ('://shrunkin\.com'),
+    ('://shw\.me'),
+    ('://shy\.si'),
+    ('://sicax\.net'),
+    ('://sina\.lt'),
+    ('://sk\.gy'),
+    ('://skr\.sk'),
+    ('://skroc\.pl'),
+    ('://smll\.co'),
+    ('://sn\.im'),
+    ('://sn\.vc'),
+    ('://snipr\.com'),
+    ('://snipurl\.com'),
+    ('://snsw\.us'),
+    ('://snurl\.com'),
+    ('://soo\.gd'),
+    ('://sp2\.ro'),
+    ('://spedr\.com'),
+    ('://spn\.sr'),
+    ('://sptfy\.com'),
+    ('://sq6\.ru'),
+    ('://sqrl\.it'),
+    ('://ssl\.gs'),
+    ('://sturly\.com'),
+    ('://su\.pr'),
+    ('://surl\.me'),
+    ('://sux\.cz'),
+    ('://sy\.pe'),
+    ('://t\.cn'),
+    ('://t\.co'),
+    ('://t\.ly'),
+    ('://ta\.gd'),
+    ('://tabzi\.com'),
+    ('://tau\.pe'),
+    ('://tcrn\.ch'),
+    ('://tdjt\.cz'),
+    ('://thesa\.us'),
+    ('://thinfi\.com'),
+    ('://thrdl\.es'),
+    ('://tin\.li'),
+    ('://tini\.cc'),
+    ('://tiny\.cc'),
+    ('://tiny\.lt'),
+    ('://tiny\.ms'),
+    ('://tiny\.pl'),
+    ('://tiny123\.com'),
+    ('://tinyarro\.ws'),
+    ('://tinytw\.it'),
+    ('://tinyuri\.ca'),
+    ('://tinyurl\.com'),
+    ('://tinyurl\.hu'),
+    ('://tinyvid\.io'),
+    ('://tixsu\.com'),
+    ('://tldr\.sk'),
+    ('://tldrify\.com'),
+    ('://tllg\.net'),
+    ('://tnij\.org'),
+    ('://tny\.cz'),
+    ('://tny\.im'),
+    ('://to\.ly'),
+    ('://togoto\.us'),
+    ('://tohle\.de'),
+    ('://tpmr\.com'),
+    ('://tr\.im'),
+    ('://tr\.my'),
+    ('://tr5\.in'),
+    ('://trck\.me'),
+    ('://trick\.ly'),
+    ('://trkr\.ws'),
+    ('://trunc\.it'),
+    ('://turo\.us'),
+    ('://tweetburner\.com'),
+    ('://twet\.fr'),
+    ('://twi\.im'),
+    ('://twirl\.at'),
+    ('://twit\.ac'),
+    ('://twitterpan\.com'),
+    ('://twitthis\.com'),
+    ('://twiturl\.de'),
+    ('://twlr\.me'),
+    ('://twurl\.cc'),
+    ('://twurl\.nl'),
+    ('://u\.mavrev\.com'),
+    ('://u\.nu'),
+    ('://u6e\.de'),
+    ('://ub0\.cc'),
+    ('://uby\.es'),
+    ('://ucam\.me'),
+    ('://ug\.cz'),
+    ('://ulmt\.in'),
+    ('://unlc\.us'),
+    ('://updating\.me'),
+    ('://upzat\.com'),
+    ('://ur1\.ca'),
+    ('://url\.co\.uk'),
+    ('://url2\.fr'),
+    ('://url4\.eu'),
+    ('://url5\.org'),
+    ('://urlao\.com'),
+    ('://urlbrief\.com'),
+    ('://urlcover\.com'),
+    ('://urlcut\.com'),
+    ('://urlenco\.de'),
+    ('://urlin\.it'),
+    ('://urlkiss\.com'),
+    ('://urlkr\.com'),
+    ('://urlot\.com'),
+    ('://urlpire\.com'),
+    ('://urls\.fr'),
+    ('://urlx\.ie'),
+    ('://urlx\.org'),
+    ('://urlz\.fr'),
+    ('://urlzen\.com'),
+    ('://urub\.us'),
+    ('://utfg\.sk'),
+    ('://v\.gd'),
+    ('://v\.ht'),
+    ('://v5\.gd'),
+    ('://vaaa\.fr'),
+    ('://valv\.im'),
+    ('://vaza\.me'),
+    ('://vbly\.us'),
+    ('://vd55\.com'),
+    ('://verd\.in'),
+    ('://vgn\.me'),
+    ('://virl\.com'),
+    ('://vl\.am'),
+    ('://vov\.li'),
+    ('://vsll\.eu'),
+    ('://vt802\.us'),
+    ('://vur\.me'),
+    ('://vv\.vg'),
+    ('://w1p\.fr'),
+    ('://w3t\.org'),
+    ('://wa\.link'),
+    ('://waa\.ai'),
+    ('://wb1\.eu'),
+    ('://web99\.eu'),
+    ('://wed\.li'),
+    ('://wideo\.fr'),
+    ('://wipi\.es'),
+    ('://wp\.me'),
+    ('://wtc\.la'),
+    ('://wu\.cz'),
+    ('://ww7\.fr'),
+    ('://wwy\.me'),
+    ('://x\.co/'),
+    ('://x\.nu'),
+    ('://x\.se'),
+    ('://x10\.mx'),
+    ('://x2c\.eu'),
+    ('://x2c\.eumx'),
+    ('://xaddr\.com'),
+    ('://xav\.cc'),
+    ('://xgd\.in'),
+    ('://xib\.me'),
+    ('://xl8\.eu'),
+    ('://xn--ki8h\.ws'),
+    ('://xn--kn8h\.to'),
+    ('://xn--vi8hl0c\.ws'),
+    ('://xoe\.cz'),
+    ('://xr\.com'),
+    ('://xrl\.in'),
+    ('://xrl\.us'),
+    ('://xt3\.me'),
+    ('://xua\.me'),
+    ('://xub\.me'),
+    ('://xurl\.jp'),
+    ('://xurls\.co'),
+    ('://xzb\.cc'),
+    ('://y2u\.be'),
+    ('://yagoa\.fr'),
+    ('://yagoa\.me'),
+    ('://yau\.sh'),
+    ('://yeca\.eu'),
+    ('://yect\.com'),
+    ('://yep\.it'),
+    ('://yfrog\.com'),
+    ('://yogh\.me'),
+    ('://yon\.ir'),
+    ('://youfap\.me'),
+    ('://ysear\.ch'),
+    ('://yweb\.com'),
+    ('://yyv\.co'),
+    ('://z9\.fr'),
+    ('://zSMS\.net'),
+    ('://zapit\.nu'),
+    ('://zeek\.ir'),
+    ('://zi\.ma'),
+    ('://zi\.pe'),
+    ('://zip\.net'),
+    ('://zipmyurl\.com'),
+    ('://zkr\.cz'),
+    ('://zkrat\.me'),
+    ('://zkrt\.cz'),
+    ('://zoodl\.com'),
+    ('://zpag\.es'),
+    ('://zti\.me'),
+    ('://zxq\.net'),
+    ('://zyva\.org'),
+    ('://zz\.gd'),
+    ('://zzb\.bz'),
+    ('://clkmein\.com # sh\.st''s ad domain'),
+    ('://cutt\.ly'),
+    ('://vo\.la'),
+    ('://yi\.tl'),
+    ('://buzurl\.com'),
+    ('://u\.bb'),
+    ('://prettylinkpro\.com'),
+    ('://prettylinks\.com'),
+    ('://scrnch\.me'),
+    ('://filoops\.info'),
+    ('://vzturl\.com'),
+    ('://tweez\.me'),
+    ('://link\.zip\.net'),
+    ('://tinyarrows\.com'),
+    ('://➡\.ws'),
+    ('://✩\.ws'),
+    ('://## Dead ones'),
+    ('://adcraft\.co'),
+    ('://adcrun\.ch'),
+    ('://adflav\.com'),
+    ('://cektkp\.com'),
+    ('://fun\.ly'),
+    ('://gog\.il'),
+    ('://golinks\.co'),
+    ('://hit\.my'),
+    ('://id\.tl'),
+    ('://linkto\.im'),
+    ('://p6l\.org'),
+    ('://picz\.us'),
+    ('://shortquik\.com'),
+    ('://tota2\.com'),
+    ('://xlinkz\.info'),
+    ('://xtu\.me'),
+    ('://yu2\.it'),
+    ('://# URL enlargers'),
+    ('://megaurl\.it'),
+    ('://enlar\.gr'),
+    ('://0nk\.de'),
+    ('://10\.rs'),
+    ('://101\.gs'),
+    ('://1kh\.de'),
+    ('://1to\.eu'),
+    ('://2\.ly'),
+    ('://2short\.net'),
+    ('://2ty\.cc'),
+    ('://2ze\.us'),
+    ('://3lr\.us'),
+    ('://5pl\.us'),
+    ('://5v5\.net'),
+    ('://6t4\.us'),
+    ('://apu\.sh'),
+    ('://ab\.cd'),
+    ('://abce5\.com'),
+    ('://acbox\.com'),
+    ('://alturl\.com'),
+    ('://ao\.co\.za'),
+    ('://avoo\.net'),
+    ('://beam\.to'),
+    ('://biglnk\.com'),
+    ('://biz\.cc'),
+    ('://boob\.tk'),
+    ('://bquot\.com'),
+    ('://brief\.ly'),
+    ('://by\.tl'),
+    ('://captus\.it'),
+    ('://cas\.im'),
+    ('://cauzes\.com'),
+    ('://celfra\.me'),
+    ('://checkthislink\.com'),
+    ('://chrst\.ph'),
+    ('://clickmeter\.com'),
+    ('://clicky\.me'),
+    ('://clockurl\.com'),
+    ('://coge\.la'),
+    ('://comyonet\.com'),
+    ('://cr\.am'),
+    ('://cut\.by'),
+    ('://cut\.lv'),
+    ('://cut\.vg'),
+    ('://url\.cutpit\.com'),
+    ('://dashurl\.com'),
+    ('://delivr\.com'),
+    ('://digbig\.com'),
+    ('://tweak\.tk'),
+    ('://durl\.me'),
+    ('://dzl\.me'),
+    ('://eachurl\.com'),
+    ('://earlme\.com'),
+    ('://easyurl\.net'),
+    ('://eg3\.net'),
+    ('://ej\.uz'),
+    ('://elurl\.com'),
+    ('://eulin\.co'),
+    ('://eurl\.in'),
+    ('://eyk\.me'),
+    ('://ez\.gd'),
+    ('://fav7\.com'),
+    ('://flib\.in'),
+    ('://flq\.us'),
+    ('://freedomain\.co\.nr'),
+    ('://freeurl\.cc'),
+    ('://fun\.to'),
+    ('://fuseurl\.com'),
+    ('://fwdurl\.net'),
+    ('://gentleurl\.net'),
+    ('://gkurl\.us'),
+    ('://gowat\.ch'),
+    ('://go\.to'),
+    ('://goder\.co\.cc'),
+    ('://gu\.ma'),
+    ('://hiderefer\.com'),
+    ('://hidelinks\.com'),
+    ('://ho\.io'),
+    ('://hostviet\.biz'),
+    ('://hotredirect\.com'),
+    ('://hotshorturl\.com'),
+    ('://hotshr\.com'),
+    ('://href\.to'),
+    ('://i\.cx'),
+    ('://i2h\.de'),
+    ('://ih\.to'),
+    ('://in2s\.us'),
+    ('://ir\.pe'),
+    ('://iscool\.net'),
+    ('://ito\.mx'),
+    ('://iz\.sg'),
+    ('://j2j\.de'),
+    ('://jive\.to'),
+    ('://just\.as'),
+    ('://keoyl\.com'),
+    ('://ki\.tl'),
+    ('://kickme\.to'),
+    ('://kly\.so'),
+    ('://krz\.ch'),
+    ('://kuerzer\.de'),
+    ('://l9k\.net'),
+    ('://link\.aero'),
+    ('://linkasa\.fr'),
+    ('://linkasa\.com'),
+    ('://linkii\.net'),
+    ('://linkpot\.net'),
+    ('://linkr\.fr'),
+    ('://links2\.me'),
+    ('://linkzip\.net'),
+    ('://lb\.vg'),
+    ('://lnk\.by'),
+    ('://lnks\.it'),
+    ('://lovebyt\.es'),
+    ('://lsua\.it'),
+    ('://lt\.vc'),
+    ('://lu\.ly'),
+    ('://lynx\.my'),
+    ('://makeashorterlink\.com'),
+    ('://many\.at'),
+    ('://memurl\.com'),
+    ('://merky\.de'),
+    ('://minilink\.org'),
+    ('://lnk\.nu'),
+    ('://minyurl\.net'),
+    ('://mo\.by'),
+    ('://mrte\.ch'),
+    ('://mtny\.mobi'),
+    ('://multiurl\.com'),
+    ('://murl\.it'),
+    ('://myurl\.si'),
+    ('://nano-url\.appspot\.com'),
+    ('://nanolink\.info'),
+    ('://nurl\.me'),
+    ('://ne8\.org'),
+    ('://normalurl\.com'),
+    ('://notifyurl\.com'),
+    ('://null-url\.com'),
+    ('://nutshellurl\.com'),
+    ('://ooqx\.com'),
+    ('://ovy\.in'),
+    ('://oym\.be'),
+    ('://pendek\.in'),
+    ('://petiteadresse\.com'),
+    ('://phlpn\.es'),
+    ('://plzlink\.me'),
+    ('://ponyurl\.com'),
+    ('://ptitlien\.com'),
+    ('://ptiturl\.com'),
+    ('://ptl\.me'),
+    ('://qlnk\.net'),
+    ('://qoiob\.com'),
+    ('://qr\.cx'),
+    ('://qt\.vc'),
+    ('://qte\.me'),
+    ('://qy\.fi'),
+    ('://re\.p\.ly'),
+    ('://redirx\.com'),
+    ('://rnm\.me'),
+    ('://rrt\.me'),
+    ('://s4c\.in'),
+    ('://sameurl\.com'),
+    ('://scr\.im'),
+    ('://shadyurl\.com'),
+    ('://shim\.net'),
+    ('://shortn\.me'),
+    ('://shortner\.com'),
+    ('://hull\.ac'),
+    ('://shrinkurl\.us'),
+    ('://shrt\.fr'),
+    ('://shrvl\.com'),
+    ('://simurl\.com'),
+    ('://sincere\.ly'),
+    ('://sl\.ly'),
+    ('://slki\.ru'),
+    ('://smal\.ly'),
+    ('://smarturl\.eu'),
+    ('://smlr\.tk'),
+    ('://smrls\.tk'),
+    ('://smsh\.me'),
+    ('://smurl\.lv'),
+    ('://cl\.lk'),
+    ('://socuteurl\.com'),
+    ('://splashurl\.net'),
+    ('://srs\.li'),
+    ('://stito\.net'),
+    ('://surl\.co\.uk'),
+    ('://surl\.hu'),
+    ('://swturl\.com'),
+    ('://t1ny\.us'),
+    ('://tad\.ly'),
+    ('://takeme\.to'),
+    ('://tbest\.me'),
+    ('://te\.tl'),
+    ('://thatis\.me'),
+    ('://thecow\.me'),
+    ('://tinfi\.com'),
+    ('://thnlnk\.com'),
+    ('://3\.ly'),
+    ('://tighturl\.com'),
+    ('://tim\.pe'),
+    ('://tiniuri\.com'),
+    ('://tiny\.ly'),
+    ('://tiny9\.com'),
+    ('://tinydb\.org'),
+    ('://tinyden\.com'),
+    ('://tinylink\.in'),
+    ('://tmpto\.net'),
+    ('://tnij\.com'),
+    ('://to\.je'),
+    ('://to\.vg'),
+    ('://tra\.kz'),
+    ('://trusturl\.com'),
+    ('://turl\.ca'),
+    ('://tutz\.me'),
+    ('://tw6\.us'),
+    ('://twtr\.us'),
+    ('://u\.n33t\.net'),
+    ('://u2r\.in'),
+    ('://u2s\.ir'),
+    ('://uanurl\.com'),
+    ('://ulimit\.com'),
+    ('://ulinks\.net'),
+    ('://unfake\.it'),
+    ('://ur\.ly'),
+    ('://urlms\.com'),
+    ('://url-zip\.com'),
+    ('://url\.az'),
+    ('://url\.eu'),
+    ('://url\.go\.it'),
+    ('://url\.ie'),
+    ('://url\.norule\.net'),
+    ('://url\.rexbd\.net'),
+    ('://urlarea\.com'),
+    ('://urlborg\.com'),
+    ('://urlcut\.me'),
+    ('://urli\.nl'),
+    ('://urlit\.net'),
+    ('://urlmini\.net'),
+    ('://urlnumber\.com'),
+    ('://urloid\.com'),
+    ('://urlredo\.com'),
+    ('://ut\.ag'),
+    ('://ux\.lv'),
+    ('://vani\.sh'),
+    ('://vdirect\.com'),
+    ('://visu\.al'),
+    ('://vm\.lc'),
+    ('://w3b\.me'),
+    ('://waldst\.at'),
+    ('://wbmaistr\.ws'),
+    ('://webalias\.com'),
+    ('://whatel\.se'),
+    ('://whereel\.se'),
+    ('://widg\.me'),
+    ('://woosah\.net'),
+    ('://wooshorturl\.com'),
+    ('://wurl\.ws'),
+    ('://wurl\.co'),
+    ('://x\.vu'),
+    ('://xeeurl\.com'),
+    ('://xqno\.com'),
+    ('://xurl\.es'),
+    ('://xxsurl\.com'),
+    ('://yoolink\.to'),
+    ('://yourl\.nu'),
+    ('://yuarel\.com'),
+    ('://yvy\.me'),
+    ('://zzapit\.nu'),
+    ('://zud\.me'),
+    ('://zu\.ly'),
+    ('://zumlink\.com'),
+    ('://zurl\.ws'),
+    ('://zxc9\.com'),
+    ('trait-sniper\.net'),
+    ('://nortons\.cloud'),
+    ('go-link\.ru'),
+    ('telegra\.ph'),
+    ('://qptr\.ru'),
+    ('pqwtazepazxe\.top'),
+    ('://sc\.link'),
+    ('steamcommutity'),
+    ('hurlurl\.com'),
+    ('steaincommunity')
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,15 @@
+CREATE TYPE FilterEscalation AS ENUM ('none', 'kick', 'ban');
+
+ALTER TABLE filter_settings
+    ADD COLUMN timeout_minutes INT NOT NULL DEFAULT 0,
+    ADD COLUMN escalation FilterEscalation NOT NULL DEFAULT 'none',
+    ADD COLUMN escalation_strikes INT NOT NULL DEFAULT 3,
+    ADD COLUMN strike_window_minutes INT NOT NULL DEFAULT 1440;
+
+CREATE TABLE filter_strikes (
+    guild_id BIGINT NOT NULL REFERENCES guilds(guild_id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL,
+    strikes INT NOT NULL,
+    window_started_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (guild_id, user_id)
+);
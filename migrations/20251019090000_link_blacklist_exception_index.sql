@@ -0,0 +1,6 @@
+-- a guild can blacklist a pattern and except it too, e.g. blacklist a domain and except one path
+-- on it, so the kind is part of what makes an entry unique.
+DROP INDEX link_blacklist_guild_pattern_idx;
+CREATE UNIQUE INDEX link_blacklist_guild_pattern_idx
+    ON link_blacklist (guild_id, pattern, is_exception)
+    WHERE guild_id IS NOT NULL;
@@ -0,0 +1,48 @@
+// Records every command invocation in `executed_commands`, wired up as framework hooks in main.
+// Invocations the framework rejects before running, like failed checks, cooldowns or bad
+// arguments, aren't recorded.
+
+use mothy_core::database::{CommandType, ExecutedCommand};
+
+use crate::{Context, Data, Error};
+
+pub async fn post_command(ctx: Context<'_>) {
+    record(ctx, None).await;
+}
+
+pub async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+    if let poise::FrameworkError::Command { ctx, .. }
+    | poise::FrameworkError::CommandPanic { ctx, .. } = &error
+    {
+        record(*ctx, Some(error.to_string())).await;
+    }
+
+    mothy_core::error::command_handler(error).await;
+}
+
+async fn record(ctx: Context<'_>, error_text: Option<String>) {
+    let executed = ExecutedCommand {
+        user_id: ctx.author().id,
+        channel_id: ctx.channel_id(),
+        guild_id: ctx.guild_id(),
+        command: ctx.command().qualified_name.to_string(),
+        command_type: command_type(ctx),
+        executed_at: ctx.created_at(),
+        error_text,
+    };
+
+    if let Err(e) = ctx.data().database.insert_executed_command(executed).await {
+        tracing::error!(error = %e, "Failed to record command execution");
+    }
+}
+
+fn command_type(ctx: Context<'_>) -> CommandType {
+    match ctx {
+        poise::Context::Application(_) => CommandType::Application,
+        poise::Context::Prefix(prefix) => match prefix.trigger {
+            poise::MessageDispatchTrigger::MessageCreate => CommandType::Prefix,
+            poise::MessageDispatchTrigger::MessageEdit => CommandType::PrefixEdited,
+            poise::MessageDispatchTrigger::MessageEditFromInvalid => CommandType::PrefixUntracked,
+        },
+    }
+}
@@ -0,0 +1,108 @@
+// Checks for the bot's own permissions, see `ModRolePermissions`. These stand in for
+// `required_permissions`, so a command using one shouldn't also set `default_member_permissions`
+// or members who only have a mod role won't see it.
+
+use mothy_core::database_models::ModRolePermissions;
+use poise::serenity_prelude as serenity;
+
+use crate::{Context, Error};
+
+/// The author's roles and Discord permissions in the current guild.
+pub async fn author_roles_and_permissions(
+    ctx: Context<'_>,
+) -> Option<(Vec<serenity::RoleId>, serenity::Permissions)> {
+    let member = ctx.author_member().await?;
+    let permissions = ctx.guild()?.member_permissions(&member);
+
+    Some((member.roles.to_vec(), permissions))
+}
+
+/// The author's effective bot permissions, empty outside of guilds.
+pub async fn author_mod_permissions(ctx: Context<'_>) -> Result<ModRolePermissions, Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(ModRolePermissions::empty());
+    };
+    let Some((roles, permissions)) = author_roles_and_permissions(ctx).await else {
+        return Ok(ModRolePermissions::empty());
+    };
+
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    Ok(ModRolePermissions::resolve(
+        &guild_settings.mod_roles,
+        &roles,
+        permissions,
+    ))
+}
+
+/// Tells the author they can't use the command, for checks that fail.
+pub async fn deny(ctx: Context<'_>) -> Result<bool, Error> {
+    ctx.send(
+        poise::CreateReply::default()
+            .content("You don't have permission to use this command.")
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(false)
+}
+
+/// Passes if the author has every permission in `required`, replying if they don't.
+pub async fn require(ctx: Context<'_>, required: ModRolePermissions) -> Result<bool, Error> {
+    if author_mod_permissions(ctx).await?.contains(required) {
+        Ok(true)
+    } else {
+        deny(ctx).await
+    }
+}
+
+pub async fn manage_sticky_roles(ctx: Context<'_>) -> Result<bool, Error> {
+    require(ctx, ModRolePermissions::MANAGE_STICKY_ROLES).await
+}
+
+pub async fn manage_cotd(ctx: Context<'_>) -> Result<bool, Error> {
+    require(ctx, ModRolePermissions::MANAGE_COTD).await
+}
+
+pub async fn manage_filters(ctx: Context<'_>) -> Result<bool, Error> {
+    require(ctx, ModRolePermissions::MANAGE_FILTERS).await
+}
+
+pub async fn view_logs(ctx: Context<'_>) -> Result<bool, Error> {
+    require(ctx, ModRolePermissions::VIEW_LOGS).await
+}
+
+/// Framework-wide check refusing bot-banned users and banned guilds, owners always pass.
+pub async fn not_banned(ctx: Context<'_>) -> Result<bool, Error> {
+    if ctx.framework().options().owners.contains(&ctx.author().id) {
+        return Ok(true);
+    }
+
+    let database = &ctx.data().database;
+    if database
+        .is_bot_banned(ctx.author().id)
+        .await
+        .map_err(Error::from_anyhow)?
+    {
+        return Ok(false);
+    }
+
+    if let Some(guild_id) = ctx.guild_id() {
+        let guild_settings = database
+            .guild_handler
+            .get(guild_id)
+            .await
+            .map_err(Error::from_anyhow)?;
+        if guild_settings.banned() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
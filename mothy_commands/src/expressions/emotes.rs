@@ -0,0 +1,184 @@
+use mothy_core::database_models::EmoteUsageType;
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+use super::{StatsOptions, Window, send_counts, send_unused};
+use crate::{Context, Error};
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum Usage {
+    #[name = "Messages"]
+    Messages,
+    #[name = "Reactions"]
+    Reactions,
+}
+
+impl From<Usage> for EmoteUsageType {
+    fn from(usage: Usage) -> Self {
+        match usage {
+            Usage::Messages => EmoteUsageType::Message,
+            Usage::Reactions => EmoteUsageType::Reaction,
+        }
+    }
+}
+
+/// See which custom emotes get used in this server.
+#[poise::command(
+    rename = "emote-stats",
+    slash_command,
+    prefix_command,
+    category = "Expressions",
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS",
+    default_member_permissions = "MANAGE_GUILD_EXPRESSIONS",
+    subcommands("emote_stats_top", "emote_stats_bottom", "emote_stats_unused"),
+    subcommand_required
+)]
+pub async fn emote_stats(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show the most used emotes.
+#[poise::command(
+    rename = "top",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS"
+)]
+pub async fn emote_stats_top(
+    ctx: Context<'_>,
+    #[description = "How far back to look"] window: Option<Window>,
+    #[description = "Only count emotes used by this user"] user: Option<serenity::User>,
+    #[description = "Only count emotes used in this channel"] channel: Option<
+        serenity::GuildChannel,
+    >,
+    #[description = "Only count emotes in messages or reactions"] usage: Option<Usage>,
+    #[description = "How many emotes to show"] count: Option<u8>,
+    #[description = "Export every emote as CSV"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let options = StatsOptions {
+        window: window.unwrap_or_default(),
+        user,
+        channel: channel.map(|c| c.id.widen()),
+        count,
+        csv: csv.unwrap_or_default(),
+    };
+    emote_counts(ctx, options, usage, false).await
+}
+
+/// Show the least used emotes.
+#[poise::command(
+    rename = "bottom",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS"
+)]
+pub async fn emote_stats_bottom(
+    ctx: Context<'_>,
+    #[description = "How far back to look"] window: Option<Window>,
+    #[description = "Only count emotes used by this user"] user: Option<serenity::User>,
+    #[description = "Only count emotes used in this channel"] channel: Option<
+        serenity::GuildChannel,
+    >,
+    #[description = "Only count emotes in messages or reactions"] usage: Option<Usage>,
+    #[description = "How many emotes to show"] count: Option<u8>,
+    #[description = "Export every emote as CSV"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let options = StatsOptions {
+        window: window.unwrap_or_default(),
+        user,
+        channel: channel.map(|c| c.id.widen()),
+        count,
+        csv: csv.unwrap_or_default(),
+    };
+    emote_counts(ctx, options, usage, true).await
+}
+
+/// Show this server's emotes that haven't been used.
+#[poise::command(
+    rename = "unused",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS"
+)]
+pub async fn emote_stats_unused(
+    ctx: Context<'_>,
+    #[description = "How far back to look"] window: Option<Window>,
+    #[description = "Export the emotes as CSV"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let options = StatsOptions {
+        window: window.unwrap_or_default(),
+        user: None,
+        channel: None,
+        count: None,
+        csv: csv.unwrap_or_default(),
+    };
+    let guild_id = ctx.guild_id().unwrap();
+
+    // every used emote is needed to work out which ones weren't.
+    let mut filter = options.filter(guild_id, false);
+    filter.limit = None;
+    let used = ctx
+        .data()
+        .database
+        .get_emote_stats(&filter, None)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let server_emotes: Vec<(u64, String)> = ctx
+        .guild()
+        .map(|guild| {
+            guild
+                .emojis
+                .iter()
+                .map(|emoji| (emoji.id.get(), emoji.name.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    send_unused(
+        ctx,
+        "Unused Emotes",
+        &options,
+        server_emotes,
+        &used,
+        |id, name| format!("<:{name}:{id}>"),
+    )
+    .await
+}
+
+async fn emote_counts(
+    ctx: Context<'_>,
+    options: StatsOptions,
+    usage: Option<Usage>,
+    ascending: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let counts = ctx
+        .data()
+        .database
+        .get_emote_stats(&options.filter(guild_id, ascending), usage.map(Into::into))
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let title = match (ascending, usage) {
+        (false, None) => "Top Emotes",
+        (true, None) => "Bottom Emotes",
+        (false, Some(Usage::Messages)) => "Top Emotes in Messages",
+        (true, Some(Usage::Messages)) => "Bottom Emotes in Messages",
+        (false, Some(Usage::Reactions)) => "Top Reaction Emotes",
+        (true, Some(Usage::Reactions)) => "Bottom Reaction Emotes",
+    };
+
+    send_counts(ctx, title, &options, &counts, |count| {
+        format!("<:{}:{}> `{}`", count.name, count.id, count.name)
+    })
+    .await
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [emote_stats()]
+}
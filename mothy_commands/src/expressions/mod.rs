@@ -0,0 +1,248 @@
+pub mod emotes;
+pub mod stickers;
+
+use mothy_core::{
+    database::{ExpressionCount, ExpressionStatsFilter},
+    database_models::GuildFeatures,
+};
+use poise::{
+    ChoiceParameter, CreateReply,
+    serenity_prelude::{self as serenity, CreateAttachment, CreateEmbed, CreateEmbedFooter},
+};
+
+use crate::{Context, Error};
+
+/// How many entries an embed lists when no count is given.
+const DEFAULT_COUNT: u8 = 10;
+/// Embed descriptions are capped at 4096 characters, keep well clear of it.
+const MAX_COUNT: u8 = 50;
+
+#[derive(ChoiceParameter, Clone, Copy, Default)]
+pub enum Window {
+    #[name = "Past day"]
+    Day,
+    #[name = "Past week"]
+    Week,
+    #[name = "Past month"]
+    Month,
+    #[name = "Past year"]
+    Year,
+    #[default]
+    #[name = "All time"]
+    AllTime,
+}
+
+impl Window {
+    fn days(self) -> Option<i32> {
+        match self {
+            Window::Day => Some(1),
+            Window::Week => Some(7),
+            Window::Month => Some(30),
+            Window::Year => Some(365),
+            Window::AllTime => None,
+        }
+    }
+}
+
+/// The options shared by every statistics subcommand.
+struct StatsOptions {
+    window: Window,
+    user: Option<serenity::User>,
+    channel: Option<serenity::GenericChannelId>,
+    count: Option<u8>,
+    csv: bool,
+}
+
+impl StatsOptions {
+    fn filter(&self, guild_id: serenity::GuildId, ascending: bool) -> ExpressionStatsFilter {
+        ExpressionStatsFilter {
+            guild_id,
+            user_id: self.user.as_ref().map(|u| u.id),
+            channel_id: self.channel,
+            window_days: self.window.days(),
+            ascending,
+            // exports get everything, embeds only what fits.
+            limit: if self.csv {
+                None
+            } else {
+                Some(i64::from(
+                    self.count.unwrap_or(DEFAULT_COUNT).clamp(1, MAX_COUNT),
+                ))
+            },
+        }
+    }
+
+    fn scope(&self) -> String {
+        let mut scope = self.window.name().to_string();
+        if let Some(user) = &self.user {
+            scope.push_str(&format!(", by {}", user.name));
+        }
+        if let Some(channel) = self.channel {
+            scope.push_str(&format!(", in <#{channel}>"));
+        }
+        scope
+    }
+}
+
+async fn tracking_enabled(ctx: Context<'_>) -> Result<bool, Error> {
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(ctx.guild_id().unwrap())
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    Ok(guild_settings
+        .features
+        .contains(GuildFeatures::EXPRESSION_TRACKING))
+}
+
+/// Replies with either an embed of `counts` or, when asked for, a CSV export of them.
+async fn send_counts(
+    ctx: Context<'_>,
+    title: &str,
+    options: &StatsOptions,
+    counts: &[ExpressionCount],
+    format_entry: impl Fn(&ExpressionCount) -> String,
+) -> Result<(), Error> {
+    if options.csv {
+        let attachment = CreateAttachment::bytes(
+            counts_to_csv(counts),
+            format!("{}.csv", title.to_lowercase().replace(' ', "_")),
+        );
+        ctx.send(CreateReply::default().attachment(attachment))
+            .await?;
+        return Ok(());
+    }
+
+    let description = if counts.is_empty() {
+        "Nothing was used.".to_string()
+    } else {
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                format!(
+                    "`{}.` {} - {} use{}",
+                    i + 1,
+                    format_entry(count),
+                    count.uses,
+                    if count.uses == 1 { "" } else { "s" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut embed = CreateEmbed::default()
+        .title(title)
+        .description(format!("{}\n\n{description}", options.scope()));
+    if !tracking_enabled(ctx).await? {
+        embed = embed.footer(CreateEmbedFooter::new(
+            "Expression tracking is disabled, so nothing new is being recorded.",
+        ));
+    }
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Replies with the expressions in `all` that never show up in `used`.
+async fn send_unused(
+    ctx: Context<'_>,
+    title: &str,
+    options: &StatsOptions,
+    all: Vec<(u64, String)>,
+    used: &[ExpressionCount],
+    format_entry: impl Fn(u64, &str) -> String,
+) -> Result<(), Error> {
+    let unused: Vec<ExpressionCount> = all
+        .into_iter()
+        .filter(|(id, _)| !used.iter().any(|count| count.id == *id))
+        .map(|(id, name)| ExpressionCount { id, name, uses: 0 })
+        .collect();
+
+    if options.csv {
+        let attachment = CreateAttachment::bytes(
+            counts_to_csv(&unused),
+            format!("{}.csv", title.to_lowercase().replace(' ', "_")),
+        );
+        ctx.send(CreateReply::default().attachment(attachment))
+            .await?;
+        return Ok(());
+    }
+
+    let mut description = if unused.is_empty() {
+        "Everything was used at least once.".to_string()
+    } else {
+        unused
+            .iter()
+            .map(|count| format_entry(count.id, &count.name))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    if description.len() > 4000 {
+        description = format!(
+            "{} unused, too many to list here. Export them as CSV instead.",
+            unused.len()
+        );
+    }
+
+    let embed = CreateEmbed::default()
+        .title(title)
+        .description(format!("{}\n\n{description}", options.scope()));
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn counts_to_csv(counts: &[ExpressionCount]) -> String {
+    let mut csv = String::from("id,name,uses\n");
+    for count in counts {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            count.id,
+            csv_field(&count.name),
+            count.uses
+        ));
+    }
+    csv
+}
+
+/// Quotes a field if it contains anything CSV treats specially.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[test]
+fn test_counts_to_csv() {
+    let counts = [
+        ExpressionCount {
+            id: 1,
+            name: "moth".to_string(),
+            uses: 3,
+        },
+        ExpressionCount {
+            id: 2,
+            name: "big, \"fluffy\" moth".to_string(),
+            uses: 1,
+        },
+    ];
+    assert_eq!(
+        counts_to_csv(&counts),
+        "id,name,uses\n1,moth,3\n2,\"big, \"\"fluffy\"\" moth\",1\n"
+    );
+}
+
+#[must_use]
+pub fn commands() -> Vec<crate::Command> {
+    emotes::commands()
+        .into_iter()
+        .chain(stickers::commands())
+        .collect()
+}
@@ -0,0 +1,160 @@
+use poise::serenity_prelude as serenity;
+
+use super::{StatsOptions, Window, send_counts, send_unused};
+use crate::{Context, Error};
+
+/// See which stickers get used in this server.
+#[poise::command(
+    rename = "sticker-stats",
+    slash_command,
+    prefix_command,
+    category = "Expressions",
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS",
+    default_member_permissions = "MANAGE_GUILD_EXPRESSIONS",
+    subcommands("sticker_stats_top", "sticker_stats_bottom", "sticker_stats_unused"),
+    subcommand_required
+)]
+pub async fn sticker_stats(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show the most used stickers.
+#[poise::command(
+    rename = "top",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS"
+)]
+pub async fn sticker_stats_top(
+    ctx: Context<'_>,
+    #[description = "How far back to look"] window: Option<Window>,
+    #[description = "Only count stickers sent by this user"] user: Option<serenity::User>,
+    #[description = "Only count stickers sent in this channel"] channel: Option<
+        serenity::GuildChannel,
+    >,
+    #[description = "How many stickers to show"] count: Option<u8>,
+    #[description = "Export every sticker as CSV"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let options = StatsOptions {
+        window: window.unwrap_or_default(),
+        user,
+        channel: channel.map(|c| c.id.widen()),
+        count,
+        csv: csv.unwrap_or_default(),
+    };
+    sticker_counts(ctx, options, false).await
+}
+
+/// Show the least used stickers.
+#[poise::command(
+    rename = "bottom",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS"
+)]
+pub async fn sticker_stats_bottom(
+    ctx: Context<'_>,
+    #[description = "How far back to look"] window: Option<Window>,
+    #[description = "Only count stickers sent by this user"] user: Option<serenity::User>,
+    #[description = "Only count stickers sent in this channel"] channel: Option<
+        serenity::GuildChannel,
+    >,
+    #[description = "How many stickers to show"] count: Option<u8>,
+    #[description = "Export every sticker as CSV"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let options = StatsOptions {
+        window: window.unwrap_or_default(),
+        user,
+        channel: channel.map(|c| c.id.widen()),
+        count,
+        csv: csv.unwrap_or_default(),
+    };
+    sticker_counts(ctx, options, true).await
+}
+
+/// Show this server's stickers that haven't been used.
+#[poise::command(
+    rename = "unused",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD_EXPRESSIONS"
+)]
+pub async fn sticker_stats_unused(
+    ctx: Context<'_>,
+    #[description = "How far back to look"] window: Option<Window>,
+    #[description = "Export the stickers as CSV"] csv: Option<bool>,
+) -> Result<(), Error> {
+    let options = StatsOptions {
+        window: window.unwrap_or_default(),
+        user: None,
+        channel: None,
+        count: None,
+        csv: csv.unwrap_or_default(),
+    };
+    let guild_id = ctx.guild_id().unwrap();
+
+    // every used sticker is needed to work out which ones weren't.
+    let mut filter = options.filter(guild_id, false);
+    filter.limit = None;
+    let used = ctx
+        .data()
+        .database
+        .get_sticker_stats(&filter)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let server_stickers: Vec<(u64, String)> = ctx
+        .guild()
+        .map(|guild| {
+            guild
+                .stickers
+                .iter()
+                .map(|sticker| (sticker.id.get(), sticker.name.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    send_unused(
+        ctx,
+        "Unused Stickers",
+        &options,
+        server_stickers,
+        &used,
+        |_, name| format!("`{name}`"),
+    )
+    .await
+}
+
+async fn sticker_counts(
+    ctx: Context<'_>,
+    options: StatsOptions,
+    ascending: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let counts = ctx
+        .data()
+        .database
+        .get_sticker_stats(&options.filter(guild_id, ascending))
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let title = if ascending {
+        "Bottom Stickers"
+    } else {
+        "Top Stickers"
+    };
+
+    send_counts(ctx, title, &options, &counts, |count| {
+        format!("`{}`", count.name)
+    })
+    .await
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [sticker_stats()]
+}
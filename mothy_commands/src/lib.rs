@@ -1,7 +1,11 @@
+pub mod audit;
+pub mod checks;
+pub mod expressions;
 pub mod fun;
 pub mod management;
 pub mod meta;
 pub mod moths;
+pub mod settings;
 pub mod utility;
 
 use mothy_core::structs::CommandData;
@@ -10,6 +14,8 @@ pub use mothy_core::{
     structs::{Command, Context, Data},
 };
 
+pub const STOCK_PREFIX: &str = "m";
+
 #[must_use]
 pub fn commands() -> Vec<crate::Command> {
     let mut commands: Vec<crate::Command> = meta::commands()
@@ -18,6 +24,8 @@ pub fn commands() -> Vec<crate::Command> {
         .chain(utility::commands())
         .chain(moths::commands())
         .chain(management::commands())
+        .chain(settings::commands())
+        .chain(expressions::commands())
         .collect();
 
     if std::env::var("DEV_COMMANDS")
@@ -36,32 +44,30 @@ pub async fn try_strip_prefix<'a>(
     ctx: &serenity::all::Context,
     msg: &'a serenity::all::Message,
 ) -> Result<Option<(&'a str, &'a str)>, Error> {
-    let stock_prefix = "m";
-
     let Some(guild_id) = msg.guild_id else {
-        if msg.content.strip_prefix(stock_prefix).is_some() {
-            return Ok(Some(msg.content.split_at(stock_prefix.len())));
+        if msg.content.strip_prefix(STOCK_PREFIX).is_some() {
+            return Ok(Some(msg.content.split_at(STOCK_PREFIX.len())));
         } else {
             return Ok(None);
         }
     };
 
     let data = ctx.data_ref::<Data>();
-
-    if let Some(prefix) = data
+    let guild_settings = data
         .database
         .guild_handler
         .get(guild_id)
         .await
-        .expect("TODO: anyhow to error?")
-        .prefix
+        .map_err(Error::from_anyhow)?;
+
+    if let Some(prefix) = guild_settings.prefix
         && msg.content.strip_prefix(prefix.as_str()).is_some()
     {
         return Ok(Some(msg.content.split_at(prefix.len())));
     };
 
-    if msg.content.strip_prefix(stock_prefix).is_some() {
-        return Ok(Some(msg.content.split_at(stock_prefix.len())));
+    if !guild_settings.stock_prefix_disabled() && msg.content.strip_prefix(STOCK_PREFIX).is_some() {
+        return Ok(Some(msg.content.split_at(STOCK_PREFIX.len())));
     }
 
     Ok(None)
@@ -0,0 +1,124 @@
+use serenity::all::{GuildId, UserId};
+
+use crate::{Context, Error};
+
+#[poise::command(
+    rename = "bot-ban",
+    prefix_command,
+    hide_in_help,
+    owners_only,
+    subcommands("bot_ban_user", "bot_ban_guild"),
+    subcommand_required
+)]
+async fn bot_ban(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+#[poise::command(rename = "user", prefix_command, hide_in_help, owners_only)]
+async fn bot_ban_user(ctx: Context<'_>, user_id: u64, #[rest] reason: String) -> Result<(), Error> {
+    if !check_id(ctx, user_id).await? {
+        return Ok(());
+    }
+
+    ctx.data()
+        .database
+        .set_bot_banned(UserId::new(user_id), true, Some(&reason))
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!("Banned user `{user_id}` from the bot: {reason}"))
+        .await?;
+    Ok(())
+}
+
+#[poise::command(rename = "guild", prefix_command, hide_in_help, owners_only)]
+async fn bot_ban_guild(
+    ctx: Context<'_>,
+    guild_id: u64,
+    #[rest] reason: String,
+) -> Result<(), Error> {
+    if !check_id(ctx, guild_id).await? {
+        return Ok(());
+    }
+
+    let guild_id = GuildId::new(guild_id);
+    ctx.data()
+        .database
+        .guild_handler
+        .set_banned(guild_id, true, Some(&reason))
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let left = if ctx.cache().guilds().contains(&guild_id) {
+        ctx.http().leave_guild(guild_id).await?;
+        " and left it"
+    } else {
+        ""
+    };
+
+    ctx.say(format!("Banned guild `{guild_id}`{left}: {reason}"))
+        .await?;
+    Ok(())
+}
+
+#[poise::command(
+    rename = "bot-unban",
+    prefix_command,
+    hide_in_help,
+    owners_only,
+    subcommands("bot_unban_user", "bot_unban_guild"),
+    subcommand_required
+)]
+async fn bot_unban(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+#[poise::command(rename = "user", prefix_command, hide_in_help, owners_only)]
+async fn bot_unban_user(ctx: Context<'_>, user_id: u64) -> Result<(), Error> {
+    if !check_id(ctx, user_id).await? {
+        return Ok(());
+    }
+
+    ctx.data()
+        .database
+        .set_bot_banned(UserId::new(user_id), false, None)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!("Unbanned user `{user_id}`.")).await?;
+    Ok(())
+}
+
+#[poise::command(rename = "guild", prefix_command, hide_in_help, owners_only)]
+async fn bot_unban_guild(ctx: Context<'_>, guild_id: u64) -> Result<(), Error> {
+    if !check_id(ctx, guild_id).await? {
+        return Ok(());
+    }
+
+    ctx.data()
+        .database
+        .guild_handler
+        .set_banned(GuildId::new(guild_id), false, None)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!(
+        "Unbanned guild `{guild_id}`, it can invite the bot again."
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Replies if `id` is 0, which no Discord ID is and serenity's ID types panic on.
+async fn check_id(ctx: Context<'_>, id: u64) -> Result<bool, Error> {
+    if id == 0 {
+        ctx.say("`0` is not a valid ID.").await?;
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 2] {
+    [bot_ban(), bot_unban()]
+}
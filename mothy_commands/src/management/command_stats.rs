@@ -0,0 +1,92 @@
+use poise::CreateReply;
+use serenity::all::CreateAttachment;
+
+use crate::{Context, Error};
+
+const RECENT_FAILURES: i64 = 10;
+
+/// Usage counts and error rates of every command, or the recent failures of one.
+#[poise::command(rename = "command-stats", prefix_command, hide_in_help, owners_only)]
+async fn command_stats(ctx: Context<'_>, #[rest] command: Option<String>) -> Result<(), Error> {
+    let database = &ctx.data().database;
+
+    if let Some(command) = command {
+        let command = command.trim().to_lowercase();
+        let failures = database
+            .get_command_failures(&command, RECENT_FAILURES)
+            .await
+            .map_err(Error::from_anyhow)?;
+
+        if failures.is_empty() {
+            ctx.say(format!("No failures recorded for `{command}`."))
+                .await?;
+            return Ok(());
+        }
+
+        let failures_formatted = failures
+            .into_iter()
+            .map(|failure| {
+                format!(
+                    "{}\t{}\t{}\t{}",
+                    failure
+                        .executed_at
+                        .map(|x| x.to_string())
+                        .unwrap_or_default(),
+                    failure.user_id,
+                    failure.guild_id.map(|x| x.to_string()).unwrap_or_default(),
+                    escape(&failure.error_text.unwrap_or_default()),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        ctx.send(CreateReply::new().attachment(CreateAttachment::bytes(
+            format!("executed_at\tuser_id\tguild_id\terror_text\n{failures_formatted}\n"),
+            "command_failures.txt",
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    let usage = database
+        .get_command_usage()
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let usage_formatted = usage
+        .iter()
+        .map(|usage| {
+            format!(
+                "{}\t{}\t{}\t{:.2}%\t{}\t{}",
+                usage.command,
+                usage.uses,
+                usage.failures,
+                usage.error_rate() * 100.0,
+                usage
+                    .last_failure
+                    .map(|x| x.to_string())
+                    .unwrap_or_default(),
+                escape(usage.last_error.as_deref().unwrap_or_default()),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ctx.send(CreateReply::new().attachment(CreateAttachment::bytes(
+        format!(
+            "command\tuses\tfailures\terror_rate\tlast_failure\tlast_error\n{usage_formatted}\n"
+        ),
+        "command_stats.txt",
+    )))
+    .await?;
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\n', "\\n").replace('\t', "\\t")
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [command_stats()]
+}
@@ -0,0 +1,26 @@
+use mothy_core::structs::MothyConfig;
+
+use crate::{Context, Error};
+
+#[poise::command(rename = "reload-config", prefix_command, hide_in_help, owners_only)]
+async fn reload_config(ctx: Context<'_>) -> Result<(), Error> {
+    let config = match MothyConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            ctx.say(format!(
+                "Failed to load the config, keeping the current one: {e}"
+            ))
+            .await?;
+            return Ok(());
+        }
+    };
+
+    *ctx.data().config.write().unwrap() = config;
+    ctx.say("Reloaded the config.").await?;
+    Ok(())
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [reload_config()]
+}
@@ -1,6 +1,14 @@
+pub mod bans;
+pub mod command_stats;
+pub mod config;
 pub mod guilds;
 
 #[must_use]
 pub fn commands() -> Vec<crate::Command> {
-    guilds::commands().into_iter().collect()
+    guilds::commands()
+        .into_iter()
+        .chain(bans::commands())
+        .chain(command_stats::commands())
+        .chain(config::commands())
+        .collect()
 }
@@ -0,0 +1,235 @@
+use mothy_core::database_models::GuildFeatures;
+use poise::serenity_prelude as serenity;
+
+use crate::{Context, Error};
+
+/// Let roles post messages Discord's automod rules would block.
+#[poise::command(
+    rename = "automod-overrides",
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands(
+        "automod_overrides_list",
+        "automod_overrides_allow",
+        "automod_overrides_deny",
+        "automod_overrides_remove"
+    ),
+    subcommand_required
+)]
+pub async fn automod_overrides(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List the automod rules and the roles overriding them.
+#[poise::command(
+    rename = "list",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn automod_overrides_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let rules = guild_id.automod_rules(ctx.http()).await?;
+
+    let mut description = rules
+        .iter()
+        .map(|rule| {
+            let roles = guild_settings
+                .automod_rule_overrides
+                .iter()
+                .find(|o| o.rule_id == rule.id)
+                .map_or_else(
+                    || "No overrides".to_string(),
+                    |o| {
+                        o.roles
+                            .iter()
+                            .map(|(role_id, is_allowed)| {
+                                format!(
+                                    "<@&{role_id}> {}",
+                                    if *is_allowed { "allowed" } else { "denied" }
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    },
+                );
+            format!("**{}** (`{}`): {roles}", rule.name, rule.id)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if description.is_empty() {
+        description = "This server has no automod rules.".to_string();
+    }
+
+    if !guild_settings
+        .features
+        .contains(GuildFeatures::AUTOMODERATION)
+    {
+        description
+            .push_str("\n-# Overrides only apply once the Automoderation feature is enabled.");
+    }
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Automod Overrides")
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Repost a role's messages that an automod rule blocks.
+#[poise::command(
+    rename = "allow",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn automod_overrides_allow(
+    ctx: Context<'_>,
+    #[description = "The ID of the automod rule, see `automod-overrides list`"] rule: String,
+    #[description = "The role to exempt from the rule"] role: serenity::Role,
+) -> Result<(), Error> {
+    set_override(ctx, &rule, role.id, true).await
+}
+
+/// Never repost a role's blocked messages, even if another of their roles is allowed.
+#[poise::command(
+    rename = "deny",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn automod_overrides_deny(
+    ctx: Context<'_>,
+    #[description = "The ID of the automod rule, see `automod-overrides list`"] rule: String,
+    #[description = "The role to hold to the rule"] role: serenity::Role,
+) -> Result<(), Error> {
+    set_override(ctx, &rule, role.id, false).await
+}
+
+/// Remove a role's override for a rule.
+#[poise::command(
+    rename = "remove",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn automod_overrides_remove(
+    ctx: Context<'_>,
+    #[description = "The ID of the automod rule, see `automod-overrides list`"] rule: String,
+    #[description = "The role to remove the override of"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let Some(rule) = find_rule(ctx, &rule).await? else {
+        return Ok(());
+    };
+
+    let removed = ctx
+        .data()
+        .database
+        .guild_handler
+        .delete_automod_rule_override(guild_id, rule.id, role.id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(if removed {
+        format!(
+            "Removed the override for <@&{}> on **{}**.",
+            role.id, rule.name
+        )
+    } else {
+        format!("<@&{}> has no override on **{}**.", role.id, rule.name)
+    })
+    .await?;
+
+    Ok(())
+}
+
+async fn set_override(
+    ctx: Context<'_>,
+    rule: &str,
+    role_id: serenity::RoleId,
+    is_allowed: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let Some(rule) = find_rule(ctx, rule).await? else {
+        return Ok(());
+    };
+
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+    let was_mod_role = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .mod_roles
+        .iter()
+        .any(|mod_role| mod_role.role_id == role_id);
+
+    guild_handler
+        .set_automod_rule_override(guild_id, rule.id, role_id, is_allowed)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let mut content = if is_allowed {
+        format!(
+            "Messages from <@&{role_id}> blocked by **{}** will be reposted.",
+            rule.name
+        )
+    } else {
+        format!(
+            "Messages from <@&{role_id}> blocked by **{}** will never be reposted.",
+            rule.name
+        )
+    };
+    if !was_mod_role {
+        content.push_str(&format!(
+            "\n<@&{role_id}> is now a mod role with no permissions, `mod-roles remove` \
+             removes it along with its overrides."
+        ));
+    }
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// Looks up a rule of this server by ID, replying if there isn't one.
+async fn find_rule(ctx: Context<'_>, rule: &str) -> Result<Option<serenity::Rule>, Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let rule_id = rule.trim().parse::<u64>().ok().filter(|id| *id != 0);
+
+    let rules = guild_id.automod_rules(ctx.http()).await?;
+    let Some(rule) = rules
+        .into_iter()
+        .find(|r| rule_id.is_some_and(|id| r.id.get() == id))
+    else {
+        ctx.say(format!(
+            "`{}` isn't an automod rule in this server, see `automod-overrides list`.",
+            rule.replace('`', "")
+        ))
+        .await?;
+        return Ok(None);
+    };
+
+    Ok(Some(rule))
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [automod_overrides()]
+}
@@ -0,0 +1,893 @@
+use mothy_core::{
+    NEGATIVE_COLOR_HEX, NEUTRAL_ACTION_COLOR_HEX, POSITIVE_COLOR_HEX,
+    autoresponse::{self, channel_ancestry, find_trigger},
+    database_models::{
+        AutoresponseSettings, GuildFeatures, ModRolePermissions, Pattern, RegexTrigger,
+        TriggerContext, TriggerMetadata,
+    },
+};
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+use crate::{Context, Error, checks};
+
+/// Discord doesn't send messages longer than this.
+const MAX_RESPONSE_LEN: usize = 2000;
+
+#[derive(ChoiceParameter, Clone, Copy, Default)]
+pub enum MatchOn {
+    #[default]
+    #[name = "Message text"]
+    Text,
+    #[name = "Text in images"]
+    Images,
+    #[name = "Both"]
+    Both,
+}
+
+impl From<MatchOn> for TriggerContext {
+    fn from(match_on: MatchOn) -> Self {
+        match match_on {
+            MatchOn::Text => TriggerContext::TEXT,
+            MatchOn::Images => TriggerContext::OCR,
+            MatchOn::Both => TriggerContext::TEXT | TriggerContext::OCR,
+        }
+    }
+}
+
+/// Respond to messages matching regex triggers.
+///
+/// Mod roles with the manage triggers permission can use this, as can anyone
+/// `autoresponse-settings` allows.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    check = "can_manage_triggers",
+    subcommands(
+        "autoresponse_add",
+        "autoresponse_test",
+        "autoresponse_list",
+        "autoresponse_enable",
+        "autoresponse_disable",
+        "autoresponse_remove",
+        "autoresponse_ignore_channel",
+        "autoresponse_unignore_channel"
+    ),
+    subcommand_required
+)]
+pub async fn autoresponse(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add a trigger.
+#[poise::command(
+    rename = "add",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_add(
+    ctx: Context<'_>,
+    #[description = "The regex messages are matched against"] pattern: String,
+    #[description = "What to reply with"] response: String,
+    #[description = "Only respond in this channel or category"] channel: Option<
+        serenity::GuildChannel,
+    >,
+    #[description = "Also respond in threads and channels below the channel (default: true)"]
+    recursive: Option<bool>,
+    #[description = "Use fancy regex, which supports lookaround and backreferences"] fancy: Option<
+        bool,
+    >,
+    #[description = "Match message text, text read from attached images, or both (default: text)"]
+    match_on: Option<MatchOn>,
+) -> Result<(), Error> {
+    if response.chars().count() > MAX_RESPONSE_LEN {
+        ctx.say(format!(
+            "Responses can be at most {MAX_RESPONSE_LEN} characters, Discord won't send longer \
+             messages."
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let pattern = match Pattern::new(&pattern, fancy.unwrap_or_default()) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            ctx.say(format!("Invalid pattern: {e}")).await?;
+            return Ok(());
+        }
+    };
+
+    let trigger = RegexTrigger::new(
+        pattern,
+        channel.map(|c| c.id.widen()),
+        match_on.unwrap_or_default().into(),
+        TriggerMetadata {
+            text: Some(response),
+        },
+        recursive.unwrap_or(true),
+    );
+
+    let guild_id = ctx.guild_id().unwrap();
+    let id = ctx
+        .data()
+        .database
+        .guild_handler
+        .insert_regex_trigger(guild_id, &trigger)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!(
+        "Added trigger `{id}`.\n{}",
+        describe_trigger(&trigger)
+    ))
+    .await?;
+    log_trigger_change(ctx, id, None, Some(&trigger)).await?;
+
+    Ok(())
+}
+
+/// Check which trigger would respond to a message in this channel.
+#[poise::command(
+    rename = "test",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_test(
+    ctx: Context<'_>,
+    #[description = "The message to test"]
+    #[rest]
+    text: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let ancestry = channel_ancestry(ctx.cache(), guild_id, ctx.channel_id());
+    let content = match find_trigger(&guild_settings, TriggerContext::TEXT, &ancestry, &text) {
+        Some(trigger) => format!(
+            "Trigger `{}` would respond.\n{}",
+            trigger.id,
+            describe_trigger(trigger)
+        ),
+        None => "No trigger would respond here.".to_string(),
+    };
+
+    let content = if guild_settings
+        .features
+        .contains(GuildFeatures::AUTORESPONSE)
+    {
+        content
+    } else {
+        format!("{content}\n-# The Autoresponse feature is disabled.")
+    };
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// List the triggers in this server.
+#[poise::command(
+    rename = "list",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let mut entries: Vec<String> = guild_settings
+        .regex_triggers
+        .iter()
+        .map(|trigger| format!("**`{}`** {}", trigger.id, describe_trigger(trigger)))
+        .collect();
+
+    for trigger in &guild_settings.invalid_regex_triggers {
+        entries.push(format!(
+            "**`{}`** `{}` could not be loaded and is disabled: {}",
+            trigger.id,
+            trigger.pattern.replace('`', "\\`"),
+            trigger.reason
+        ));
+    }
+
+    if !guild_settings.regex_denylist.is_empty() {
+        entries.push(format!(
+            "**Ignored channels:** {}",
+            guild_settings
+                .regex_denylist
+                .iter()
+                .map(|entry| format!("<#{}>", entry.channel_id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    let description = if entries.is_empty() {
+        "No triggers.".to_string()
+    } else {
+        join_entries(entries)
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Autoresponse Triggers")
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Enable a trigger.
+#[poise::command(
+    rename = "enable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_enable(
+    ctx: Context<'_>,
+    #[description = "The id of the trigger"] id: u64,
+) -> Result<(), Error> {
+    set_trigger_enabled(ctx, id, true).await
+}
+
+/// Disable a trigger without removing it.
+#[poise::command(
+    rename = "disable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_disable(
+    ctx: Context<'_>,
+    #[description = "The id of the trigger"] id: u64,
+) -> Result<(), Error> {
+    set_trigger_enabled(ctx, id, false).await
+}
+
+/// Remove a trigger.
+#[poise::command(
+    rename = "remove",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_remove(
+    ctx: Context<'_>,
+    #[description = "The id of the trigger"] id: u64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let before = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .regex_triggers
+        .iter()
+        .find(|t| t.id == id)
+        .cloned();
+    let removed = guild_handler
+        .delete_regex_trigger(guild_id, id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(if removed {
+        format!("Removed trigger `{id}`.")
+    } else {
+        format!("There is no trigger `{id}`.")
+    })
+    .await?;
+    // an invalid trigger has nothing to show, so only loaded ones are logged.
+    if removed && let Some(before) = &before {
+        log_trigger_change(ctx, id, Some(before), None).await?;
+    }
+
+    Ok(())
+}
+
+/// Stop every trigger from responding in a channel.
+#[poise::command(
+    rename = "ignore-channel",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_ignore_channel(
+    ctx: Context<'_>,
+    #[description = "The channel or category to ignore"] channel: serenity::GuildChannel,
+    #[description = "Also ignore threads and channels below the channel (default: true)"]
+    recursive: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    ctx.data()
+        .database
+        .guild_handler
+        .set_regex_denylist_channel(guild_id, channel.id.widen(), recursive.unwrap_or(true))
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!(
+        "Triggers will no longer respond in <#{}>.",
+        channel.id
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Let triggers respond in an ignored channel again.
+#[poise::command(
+    rename = "unignore-channel",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "can_manage_triggers"
+)]
+pub async fn autoresponse_unignore_channel(
+    ctx: Context<'_>,
+    #[description = "The channel or category to stop ignoring"] channel: serenity::GuildChannel,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let removed = ctx
+        .data()
+        .database
+        .guild_handler
+        .delete_regex_denylist_channel(guild_id, channel.id.widen())
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(if removed {
+        format!("Triggers will respond in <#{}> again.", channel.id)
+    } else {
+        format!("<#{}> is not ignored.", channel.id)
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Choose who can manage autoresponses and where changes are logged.
+#[poise::command(
+    rename = "autoresponse-settings",
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands(
+        "autoresponse_settings_show",
+        "autoresponse_settings_allow_role",
+        "autoresponse_settings_disallow_role",
+        "autoresponse_settings_allow_permissions",
+        "autoresponse_settings_clear_permissions",
+        "autoresponse_settings_log_channel",
+        "autoresponse_settings_log_changes"
+    ),
+    subcommand_required
+)]
+pub async fn autoresponse_settings(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show who can manage autoresponses and where changes are logged.
+#[poise::command(
+    rename = "show",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn autoresponse_settings_show(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let settings = &guild_settings.autoresponse_settings;
+
+    let allowed_roles = if settings.allowed_roles.is_empty() {
+        "None".to_string()
+    } else {
+        settings
+            .allowed_roles
+            .iter()
+            .map(|id| format!("<@&{id}>"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let allowed_permissions = if settings.allowed_permissions.is_empty() {
+        "None".to_string()
+    } else {
+        settings
+            .allowed_permissions
+            .iter()
+            .map(|permissions| format!("`{permissions}`"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Autoresponse Settings")
+        .field("Allowed Roles", allowed_roles, false)
+        .field("Allowed Permissions", allowed_permissions, false)
+        .field(
+            "Logging Channel",
+            settings
+                .logging_channel_id
+                .map_or_else(|| "Not set".to_string(), |id| format!("<#{id}>")),
+            true,
+        )
+        .field(
+            "Log Changes",
+            if settings.log_changes { "Yes" } else { "No" },
+            true,
+        );
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Let a role manage autoresponses.
+#[poise::command(
+    rename = "allow-role",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn autoresponse_settings_allow_role(
+    ctx: Context<'_>,
+    #[description = "The role that can manage autoresponses"] role: serenity::Role,
+) -> Result<(), Error> {
+    update_autoresponse_settings(ctx, |settings| {
+        if settings.allowed_roles.contains(&role.id) {
+            return format!("<@&{}> can already manage autoresponses.", role.id);
+        }
+        settings.allowed_roles.push(role.id);
+        format!("<@&{}> can now manage autoresponses.", role.id)
+    })
+    .await
+}
+
+/// Stop a role from managing autoresponses.
+#[poise::command(
+    rename = "disallow-role",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn autoresponse_settings_disallow_role(
+    ctx: Context<'_>,
+    #[description = "The role that can no longer manage autoresponses"] role: serenity::Role,
+) -> Result<(), Error> {
+    update_autoresponse_settings(ctx, |settings| {
+        let previous_len = settings.allowed_roles.len();
+        settings.allowed_roles.retain(|id| *id != role.id);
+        if settings.allowed_roles.len() == previous_len {
+            format!("<@&{}> wasn't allowed to manage autoresponses.", role.id)
+        } else {
+            format!("<@&{}> can no longer manage autoresponses.", role.id)
+        }
+    })
+    .await
+}
+
+/// Let members with all of the given permissions manage autoresponses.
+#[poise::command(
+    rename = "allow-permissions",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn autoresponse_settings_allow_permissions(
+    ctx: Context<'_>,
+    #[description = "Permission names, e.g. \"MANAGE_MESSAGES, MODERATE_MEMBERS\""]
+    #[rest]
+    permissions: String,
+) -> Result<(), Error> {
+    let permissions = match parse_permissions(&permissions) {
+        Ok(permissions) => permissions,
+        Err(name) => {
+            ctx.say(format!("`{name}` isn't a permission.")).await?;
+            return Ok(());
+        }
+    };
+
+    update_autoresponse_settings(ctx, |settings| {
+        if !settings.allowed_permissions.contains(&permissions) {
+            settings.allowed_permissions.push(permissions);
+        }
+        format!("Members with `{permissions}` can now manage autoresponses.")
+    })
+    .await
+}
+
+/// Only let server managers and allowed roles manage autoresponses.
+#[poise::command(
+    rename = "clear-permissions",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn autoresponse_settings_clear_permissions(ctx: Context<'_>) -> Result<(), Error> {
+    update_autoresponse_settings(ctx, |settings| {
+        settings.allowed_permissions.clear();
+        "Permissions no longer allow managing autoresponses.".to_string()
+    })
+    .await
+}
+
+/// Set or clear the channel trigger changes are logged to.
+#[poise::command(
+    rename = "log-channel",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn autoresponse_settings_log_channel(
+    ctx: Context<'_>,
+    #[description = "The channel to log to, leave empty to stop logging"] channel: Option<
+        serenity::GuildChannel,
+    >,
+) -> Result<(), Error> {
+    let channel_id = channel.map(|c| c.id.widen());
+
+    update_autoresponse_settings(ctx, |settings| {
+        settings.logging_channel_id = channel_id;
+        match channel_id {
+            Some(channel_id) if settings.log_changes => {
+                format!("Trigger changes will be logged in <#{channel_id}>.")
+            }
+            Some(channel_id) => format!(
+                "The logging channel is now <#{channel_id}>, turn on `log-changes` to start logging."
+            ),
+            None => "Trigger changes will no longer be logged.".to_string(),
+        }
+    })
+    .await
+}
+
+/// Choose whether trigger changes are logged.
+#[poise::command(
+    rename = "log-changes",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn autoresponse_settings_log_changes(
+    ctx: Context<'_>,
+    #[description = "Whether to log trigger changes"] log_changes: bool,
+) -> Result<(), Error> {
+    update_autoresponse_settings(ctx, |settings| {
+        settings.log_changes = log_changes;
+        match (log_changes, settings.logging_channel_id) {
+            (true, Some(channel_id)) => {
+                format!("Trigger changes will be logged in <#{channel_id}>.")
+            }
+            (true, None) => {
+                "Trigger changes will be logged once a channel is set with `log-channel`."
+                    .to_string()
+            }
+            (false, _) => "Trigger changes will no longer be logged.".to_string(),
+        }
+    })
+    .await
+}
+
+/// Applies `update` to the current settings and saves them, replying with the returned message.
+async fn update_autoresponse_settings(
+    ctx: Context<'_>,
+    update: impl FnOnce(&mut AutoresponseSettings) -> String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .autoresponse_settings
+        .clone();
+
+    let content = update(&mut settings);
+    guild_handler
+        .set_autoresponse_settings(guild_id, &settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// Parses names like `MANAGE_MESSAGES` separated by commas, spaces or `|`, returning the first
+/// unknown name on failure.
+fn parse_permissions(names: &str) -> Result<serenity::Permissions, String> {
+    names
+        .split([',', '|', ' '])
+        .filter(|name| !name.is_empty())
+        .try_fold(serenity::Permissions::empty(), |permissions, name| {
+            serenity::Permissions::from_name(&name.to_uppercase())
+                .map(|permission| permissions | permission)
+                .ok_or_else(|| name.to_string())
+        })
+}
+
+async fn set_trigger_enabled(ctx: Context<'_>, id: u64, enabled: bool) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let guild_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let Some(trigger) = guild_settings
+        .regex_triggers
+        .iter()
+        .find(|t| t.id == id)
+        .cloned()
+    else {
+        let content = if guild_settings
+            .invalid_regex_triggers
+            .iter()
+            .any(|t| t.id == id)
+        {
+            format!("Trigger `{id}` could not be loaded, remove it and add it again.")
+        } else {
+            format!("There is no trigger `{id}`.")
+        };
+        ctx.say(content).await?;
+        return Ok(());
+    };
+
+    let after = trigger.clone().set_is_enabled(enabled);
+    guild_handler
+        .update_regex_trigger(guild_id, &after)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!(
+        "Trigger `{id}` is now {}.",
+        if enabled { "enabled" } else { "disabled" }
+    ))
+    .await?;
+    log_trigger_change(ctx, id, Some(&trigger), Some(&after)).await?;
+
+    Ok(())
+}
+
+/// Lets anyone with the manage triggers permission or that `autoresponse-settings` allows through.
+async fn can_manage_triggers(ctx: Context<'_>) -> Result<bool, Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(false);
+    };
+    if checks::author_mod_permissions(ctx)
+        .await?
+        .contains(ModRolePermissions::MANAGE_TRIGGERS)
+    {
+        return Ok(true);
+    }
+    let Some((roles, permissions)) = checks::author_roles_and_permissions(ctx).await else {
+        return Ok(false);
+    };
+
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    if autoresponse::can_manage_triggers(&guild_settings.autoresponse_settings, &roles, permissions)
+    {
+        Ok(true)
+    } else {
+        checks::deny(ctx).await
+    }
+}
+
+/// Posts a before/after embed of a trigger change to the logging channel, if changes are logged.
+async fn log_trigger_change(
+    ctx: Context<'_>,
+    id: u64,
+    before: Option<&RegexTrigger>,
+    after: Option<&RegexTrigger>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let settings = &guild_settings.autoresponse_settings;
+
+    let Some(logging_channel_id) = settings.logging_channel_id else {
+        return Ok(());
+    };
+    if !settings.log_changes {
+        return Ok(());
+    }
+
+    let (title, colour) = match (before, after) {
+        (None, _) => ("Trigger Added", POSITIVE_COLOR_HEX),
+        (Some(_), None) => ("Trigger Removed", NEGATIVE_COLOR_HEX),
+        (Some(_), Some(_)) => ("Trigger Edited", NEUTRAL_ACTION_COLOR_HEX),
+    };
+
+    let author = ctx.author();
+    let mut embed = serenity::CreateEmbed::new()
+        .author(
+            serenity::CreateEmbedAuthor::new(&author.name)
+                .icon_url(author.avatar_url().unwrap_or_default()),
+        )
+        .colour(colour)
+        .title(title)
+        .description(format!("Trigger `{id}` changed by <@{}>", author.id))
+        .timestamp(serenity::Timestamp::now())
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "ID: {}",
+            author.id
+        )));
+    if let Some(before) = before {
+        embed = embed.field("Before", truncate_field(describe_trigger(before)), false);
+    }
+    if let Some(after) = after {
+        embed = embed.field("After", truncate_field(describe_trigger(after)), false);
+    }
+
+    logging_channel_id
+        .send_message(
+            ctx.http(),
+            serenity::CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(serenity::CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Joins list entries with blank lines, leaving out the ones that don't fit in an embed
+/// description. Each entry is cut to a field's length so one long trigger can't hide the rest.
+fn join_entries(entries: Vec<String>) -> String {
+    const MAX_LEN: usize = 4096;
+    // room for the "…and N more" line.
+    const RESERVED: usize = 32;
+
+    let count = entries.len();
+    let mut description = String::new();
+    let mut len = 0;
+    for (index, entry) in entries.into_iter().enumerate() {
+        let entry = truncate_field(entry);
+        let entry_len = entry.chars().count() + 2;
+        let reserved = if index + 1 == count { 0 } else { RESERVED };
+        if len + entry_len + reserved > MAX_LEN {
+            description.push_str(&format!("\n\n…and {} more", count - index));
+            break;
+        }
+        if !description.is_empty() {
+            description.push_str("\n\n");
+        }
+        description.push_str(&entry);
+        len += entry_len;
+    }
+    description
+}
+
+/// Embed field values are capped at 1024 characters.
+fn truncate_field(mut value: String) -> String {
+    const MAX_LEN: usize = 1024;
+    if value.chars().count() > MAX_LEN {
+        value = value.chars().take(MAX_LEN - 1).collect();
+        value.push('…');
+    }
+    value
+}
+
+fn describe_trigger(trigger: &RegexTrigger) -> String {
+    let scope = match trigger.channel_id {
+        Some(channel_id) if trigger.is_recursive() => format!("in and below <#{channel_id}>"),
+        Some(channel_id) => format!("in <#{channel_id}>"),
+        None => "everywhere".to_string(),
+    };
+
+    let context = match (
+        trigger.trigger_context.contains(TriggerContext::TEXT),
+        trigger.trigger_context.contains(TriggerContext::OCR),
+    ) {
+        (true, true) => " in text and images",
+        (false, true) => " in images",
+        _ => "",
+    };
+
+    format!(
+        "`{}`{}{context} {scope}{}\n> {}",
+        trigger.pattern.as_str().replace('`', "\\`"),
+        if trigger.pattern.is_fancy() {
+            " (fancy)"
+        } else {
+            ""
+        },
+        if trigger.is_enabled() {
+            ""
+        } else {
+            ", disabled"
+        },
+        trigger
+            .trigger_metadata
+            .text
+            .as_deref()
+            .unwrap_or("No response"),
+    )
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 2] {
+    [autoresponse(), autoresponse_settings()]
+}
+
+#[test]
+fn test_parse_permissions() {
+    assert_eq!(
+        parse_permissions("manage_messages, MODERATE_MEMBERS"),
+        Ok(serenity::Permissions::MANAGE_MESSAGES | serenity::Permissions::MODERATE_MEMBERS)
+    );
+    assert_eq!(
+        parse_permissions("MANAGE_MOTHS"),
+        Err("MANAGE_MOTHS".to_string())
+    );
+}
+
+#[test]
+fn test_join_entries() {
+    assert_eq!(
+        join_entries(vec!["a".to_string(), "b".to_string()]),
+        "a\n\nb"
+    );
+
+    let entries = vec!["x".repeat(1000); 10];
+    let description = join_entries(entries);
+    assert!(description.chars().count() <= 4096);
+    assert!(description.ends_with("…and 7 more"));
+
+    // long entries are cut down to fit.
+    let description = join_entries(vec!["y".repeat(5000)]);
+    assert_eq!(description.chars().count(), 1024);
+}
@@ -0,0 +1,592 @@
+use mothy_core::{
+    cotd,
+    database_models::{ColourMode, CotdRoleSettings, GuildFeatures, IconPairingMode},
+};
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+use crate::{Context, Error, checks};
+
+/// How many history entries `cotd history` shows.
+const HISTORY_LIMIT: i64 = 15;
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum Mode {
+    #[name = "Random"]
+    Random,
+    #[name = "Static sequence"]
+    Static,
+}
+
+impl From<Mode> for ColourMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Random => ColourMode::Random,
+            Mode::Static => ColourMode::Static,
+        }
+    }
+}
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum IconPairing {
+    #[name = "Paired with colour"]
+    Paired,
+    #[name = "Random"]
+    Random,
+}
+
+impl From<IconPairing> for IconPairingMode {
+    fn from(pairing: IconPairing) -> Self {
+        match pairing {
+            IconPairing::Paired => IconPairingMode::Paired,
+            IconPairing::Random => IconPairingMode::Random,
+        }
+    }
+}
+
+/// Rotate role colours and icons on a daily schedule.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    check = "checks::manage_cotd",
+    subcommands(
+        "cotd_list",
+        "cotd_add",
+        "cotd_remove",
+        "cotd_configure",
+        "cotd_colours",
+        "cotd_icons",
+        "cotd_rotate",
+        "cotd_history"
+    ),
+    subcommand_required
+)]
+pub async fn cotd(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List the colour of the day roles in this server.
+#[poise::command(
+    rename = "list",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let mut embed = serenity::CreateEmbed::default()
+        .title("Colour of the Day")
+        .description(
+            if guild_settings
+                .features
+                .contains(GuildFeatures::COLOUR_OF_THE_DAY)
+            {
+                "The Colour of the Day feature is enabled."
+            } else {
+                "The Colour of the Day feature is disabled."
+            },
+        );
+
+    if guild_settings.cotd_settings.is_empty() {
+        embed = embed.field("Roles", "None", false);
+    }
+    for settings in &guild_settings.cotd_settings {
+        let role_name = ctx
+            .guild()
+            .and_then(|guild| {
+                guild
+                    .roles
+                    .get(&settings.role_id)
+                    .map(|r| r.name.to_string())
+            })
+            .unwrap_or_else(|| "Deleted Role".to_string());
+        embed = embed.field(role_name, describe_settings(settings), false);
+    }
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Start rotating a role's colour.
+#[poise::command(
+    rename = "add",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_add(
+    ctx: Context<'_>,
+    #[description = "The role to rotate"] role: serenity::Role,
+    #[description = "Space separated hex colours, use / for gradients"]
+    #[rest]
+    colours: String,
+) -> Result<(), Error> {
+    let colours = match cotd::parse_colours(&colours) {
+        Ok(colours) if colours.is_empty() => {
+            ctx.say("Give at least one colour.").await?;
+            return Ok(());
+        }
+        Ok(colours) => colours,
+        Err(reason) => {
+            ctx.say(reason).await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let guild_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if guild_settings
+        .cotd_settings
+        .iter()
+        .any(|s| s.role_id == role.id)
+    {
+        ctx.say(format!(
+            "{} is already a colour of the day role.",
+            cotd::role_mention(role.id)
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let mut settings = CotdRoleSettings::new(role.id);
+    settings.colours = colours;
+
+    guild_handler
+        .set_cotd_role_settings(guild_id, &settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!(
+        "{} will rotate between {} colours at {}.",
+        cotd::role_mention(role.id),
+        settings.colours.len(),
+        cotd::format_rotation_time(settings.rotation_time)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Stop rotating a role's colour.
+#[poise::command(
+    rename = "remove",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_remove(
+    ctx: Context<'_>,
+    #[description = "The role to stop rotating"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let removed = ctx
+        .data()
+        .database
+        .guild_handler
+        .delete_cotd_role_settings(guild_id, role.id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(if removed {
+        format!(
+            "{} is no longer a colour of the day role.",
+            cotd::role_mention(role.id)
+        )
+    } else {
+        format!(
+            "{} is not a colour of the day role.",
+            cotd::role_mention(role.id)
+        )
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Change how a colour of the day role rotates.
+#[poise::command(
+    rename = "configure",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_configure(
+    ctx: Context<'_>,
+    #[description = "The role to configure"] role: serenity::Role,
+    #[description = "Whether the role rotates"] enabled: Option<bool>,
+    #[description = "Add the current colour to the role name"] suffix: Option<bool>,
+    #[description = "How the next colour is picked"] colour_mode: Option<Mode>,
+    #[description = "How the next icon is picked"] icon_pairing: Option<IconPairing>,
+    #[description = "When to rotate, HH:MM in UTC"] rotation_time: Option<String>,
+    #[description = "The hex colour in SVG icons to replace with the role colour, \"none\" to clear"]
+    svg_target_colour: Option<String>,
+) -> Result<(), Error> {
+    let rotation_time = match rotation_time.as_deref().map(cotd::parse_rotation_time) {
+        Some(Err(reason)) => {
+            ctx.say(reason).await?;
+            return Ok(());
+        }
+        Some(Ok(time)) => Some(time),
+        None => None,
+    };
+    let svg_target_colour = match svg_target_colour.as_deref() {
+        Some("none") => Some(None),
+        Some(colour) => match cotd::parse_hex_colour(colour) {
+            Ok(colour) => Some(Some(colour)),
+            Err(reason) => {
+                ctx.say(reason).await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    update_cotd_settings(ctx, role.id, |mut settings| {
+        if let Some(enabled) = enabled {
+            settings = settings.set_is_enabled(enabled);
+        }
+        if let Some(suffix) = suffix {
+            settings = settings.set_suffix_enabled(suffix);
+        }
+        if let Some(colour_mode) = colour_mode {
+            settings.colour_mode = colour_mode.into();
+        }
+        if let Some(icon_pairing) = icon_pairing {
+            settings.icon_pairing_mode = icon_pairing.into();
+        }
+        if let Some(rotation_time) = rotation_time {
+            settings.rotation_time = rotation_time;
+        }
+        if let Some(svg_target_colour) = svg_target_colour {
+            settings.svg_target_colour = svg_target_colour;
+        }
+
+        let content = format!(
+            "Updated {}.\n{}",
+            cotd::role_mention(settings.role_id),
+            describe_settings(&settings)
+        );
+        Ok((settings, content))
+    })
+    .await
+}
+
+/// Replace the colours a role rotates between.
+#[poise::command(
+    rename = "colours",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_colours(
+    ctx: Context<'_>,
+    #[description = "The role to change"] role: serenity::Role,
+    #[description = "Space separated hex colours, use / for gradients"]
+    #[rest]
+    colours: String,
+) -> Result<(), Error> {
+    let colours = cotd::parse_colours(&colours);
+
+    update_cotd_settings(ctx, role.id, |mut settings| {
+        let colours = colours?;
+        if colours.is_empty() {
+            return Err("Give at least one colour.".to_string());
+        }
+
+        settings.colours = colours;
+        let content = format!(
+            "{} now rotates between {} colours.",
+            cotd::role_mention(settings.role_id),
+            settings.colours.len()
+        );
+        Ok((settings, content))
+    })
+    .await
+}
+
+/// Replace the icons a role rotates between.
+#[poise::command(
+    rename = "icons",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_icons(
+    ctx: Context<'_>,
+    #[description = "The role to change"] role: serenity::Role,
+    #[description = "Space separated unicode emoji or SVG file names, leave empty to clear"]
+    #[rest]
+    icons: Option<String>,
+) -> Result<(), Error> {
+    let icons: Vec<String> = icons
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(ToString::to_string)
+        .collect();
+
+    let missing_svg = icons.iter().find(|icon| {
+        icon.ends_with(".svg") && !cotd::svg_icon_path(icon).is_some_and(|path| path.is_file())
+    });
+    if let Some(icon) = missing_svg {
+        ctx.say(format!(
+            "`{}` isn't an SVG file in the icons directory.",
+            icon.replace('`', "")
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    update_cotd_settings(ctx, role.id, |mut settings| {
+        let content = if icons.is_empty() {
+            format!(
+                "{} no longer rotates icons.",
+                cotd::role_mention(settings.role_id)
+            )
+        } else {
+            format!(
+                "{} now rotates between {} icons.",
+                cotd::role_mention(settings.role_id),
+                icons.len()
+            )
+        };
+
+        settings.icons = icons;
+        Ok((settings, content))
+    })
+    .await
+}
+
+/// Rotate a role to its next colour now.
+#[poise::command(
+    rename = "rotate",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_rotate(
+    ctx: Context<'_>,
+    #[description = "The role to rotate"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let Some(settings) = guild_settings
+        .cotd_settings
+        .iter()
+        .find(|s| s.role_id == role.id)
+    else {
+        ctx.say(format!(
+            "{} is not a colour of the day role.",
+            cotd::role_mention(role.id)
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    ctx.defer().await?;
+    let rotation = cotd::rotate(
+        ctx.serenity_context(),
+        &data.database,
+        guild_id,
+        settings,
+        Some(ctx.author().id),
+    )
+    .await;
+
+    match rotation {
+        Ok(rotation) => {
+            let mut content = format!(
+                "{} is now {}",
+                cotd::role_mention(role.id),
+                cotd::format_colours(&rotation.colours)
+            );
+            if let Some(icon) = rotation.icon {
+                content.push_str(&format!(" with icon `{icon}`"));
+            }
+            content.push('.');
+            ctx.say(content).await?;
+        }
+        Err(e) => {
+            ctx.say(format!(
+                "Could not rotate {}: {e}",
+                cotd::role_mention(role.id)
+            ))
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the colours a role was given recently.
+#[poise::command(
+    rename = "history",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_cotd"
+)]
+pub async fn cotd_history(
+    ctx: Context<'_>,
+    #[description = "The role to show the history of"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let history = ctx
+        .data()
+        .database
+        .get_cotd_history(guild_id, role.id, HISTORY_LIMIT)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let description = if history.is_empty() {
+        "This role has not been given a colour yet.".to_string()
+    } else {
+        history
+            .iter()
+            .map(|entry| {
+                let when = entry.assigned_at.map_or_else(
+                    || "Unknown".to_string(),
+                    |at| format!("<t:{}:f>", at.unix_timestamp()),
+                );
+                let by = entry
+                    .assigned_by
+                    .map_or_else(|| "schedule".to_string(), |id| format!("<@{id}>"));
+                let icon = entry
+                    .icon
+                    .as_ref()
+                    .map(|icon| format!(" `{icon}`"))
+                    .unwrap_or_default();
+
+                format!("{when}: `#{:06x}`{icon} by {by}", entry.colour)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title(format!("Colour History for {}", role.name))
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn describe_settings(settings: &CotdRoleSettings) -> String {
+    let colour_mode = match settings.colour_mode {
+        ColourMode::Random => Mode::Random,
+        ColourMode::Static => Mode::Static,
+    };
+    let icon_pairing = match settings.icon_pairing_mode {
+        IconPairingMode::Paired => IconPairing::Paired,
+        IconPairingMode::Random => IconPairing::Random,
+    };
+
+    format!(
+        "Role: {}\nEnabled: {}\nRotates at: {}\nColour mode: {}\nIcon pairing: {}\nName suffix: {}\nSVG target colour: {}\nColours: {}\nIcons: {}",
+        cotd::role_mention(settings.role_id),
+        if settings.is_enabled() { "Yes" } else { "No" },
+        cotd::format_rotation_time(settings.rotation_time),
+        colour_mode.name(),
+        icon_pairing.name(),
+        if settings.suffix_enabled() {
+            "Yes"
+        } else {
+            "No"
+        },
+        settings
+            .svg_target_colour
+            .map_or_else(|| "None".to_string(), |c| format!("#{c:06x}")),
+        if settings.colours.is_empty() {
+            "None".to_string()
+        } else {
+            settings
+                .colours
+                .iter()
+                .map(cotd::format_colours)
+                .collect::<Vec<_>>()
+                .join(", ")
+        },
+        if settings.icons.is_empty() {
+            "None".to_string()
+        } else {
+            settings.icons.join(" ")
+        },
+    )
+}
+
+/// Applies `update` to a role's current settings and saves them, replying with the returned message.
+///
+/// Returning `Err` from `update` replies with the message without saving anything.
+async fn update_cotd_settings(
+    ctx: Context<'_>,
+    role_id: serenity::RoleId,
+    update: impl FnOnce(CotdRoleSettings) -> Result<(CotdRoleSettings, String), String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let Some(settings) = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .cotd_settings
+        .iter()
+        .find(|s| s.role_id == role_id)
+        .cloned()
+    else {
+        ctx.say(format!(
+            "{} is not a colour of the day role.",
+            cotd::role_mention(role_id)
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let content = match update(settings) {
+        Ok((settings, content)) => {
+            guild_handler
+                .set_cotd_role_settings(guild_id, &settings)
+                .await
+                .map_err(Error::from_anyhow)?;
+            content
+        }
+        Err(content) => content,
+    };
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [cotd()]
+}
@@ -0,0 +1,229 @@
+use mothy_core::database_models::{DmActivitySettings, GuildFeatures};
+use poise::serenity_prelude as serenity;
+
+use crate::{Context, Error, checks};
+
+/// How many members `dm-activity list` shows.
+const LIST_LIMIT: i64 = 20;
+
+// `list` is open to mod roles, so permissions are only set on the subcommands.
+/// Announce members Discord flags for unusual DM activity.
+#[poise::command(
+    rename = "dm-activity",
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    subcommands(
+        "dm_activity_show",
+        "dm_activity_channel",
+        "dm_activity_cooldown",
+        "dm_activity_retention",
+        "dm_activity_list"
+    ),
+    subcommand_required
+)]
+pub async fn dm_activity(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show the DM activity settings.
+#[poise::command(
+    rename = "show",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn dm_activity_show(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let settings = guild_settings.dm_activity_settings;
+
+    let embed = serenity::CreateEmbed::default()
+        .title("DM Activity")
+        .field(
+            "DM Activity Feature",
+            if guild_settings.features.contains(GuildFeatures::DM_ACTIVITY) {
+                "Enabled"
+            } else {
+                "Disabled"
+            },
+            true,
+        )
+        .field(
+            "Announce Channel",
+            settings
+                .announce_channel_id
+                .map_or_else(|| "Not set".to_string(), |id| format!("<#{id}>")),
+            true,
+        )
+        .field(
+            "Cooldown",
+            format!("{} seconds", settings.cooldown_seconds),
+            true,
+        )
+        .field(
+            "Retention",
+            settings
+                .retention_days
+                .map_or_else(|| "Forever".to_string(), |days| format!("{days} days")),
+            true,
+        );
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Set or clear the channel announcements are sent to.
+#[poise::command(
+    rename = "channel",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn dm_activity_channel(
+    ctx: Context<'_>,
+    #[description = "The channel to announce in, leave empty to stop announcing"] channel: Option<
+        serenity::GuildChannel,
+    >,
+) -> Result<(), Error> {
+    let channel_id = channel.map(|c| c.id.widen());
+
+    update_dm_activity_settings(ctx, |settings| {
+        settings.announce_channel_id = channel_id;
+        match channel_id {
+            Some(channel_id) => format!("DM activity will be announced in <#{channel_id}>."),
+            None => "DM activity will no longer be announced.".to_string(),
+        }
+    })
+    .await
+}
+
+/// Set the minimum time between announcements for the same member.
+#[poise::command(
+    rename = "cooldown",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn dm_activity_cooldown(
+    ctx: Context<'_>,
+    #[description = "The cooldown in minutes"]
+    #[max = 10080]
+    minutes: u32,
+) -> Result<(), Error> {
+    update_dm_activity_settings(ctx, |settings| {
+        settings.cooldown_seconds = minutes * 60;
+        format!("The same member will be announced at most once every {minutes} minutes.")
+    })
+    .await
+}
+
+/// Set how long records are kept after a flag ends.
+#[poise::command(
+    rename = "retention",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn dm_activity_retention(
+    ctx: Context<'_>,
+    #[description = "The number of days to keep records, leave empty to keep them forever"]
+    #[min = 1]
+    days: Option<u8>,
+) -> Result<(), Error> {
+    update_dm_activity_settings(ctx, |settings| {
+        settings.retention_days = days;
+        match days {
+            Some(days) => format!("DM activity records will be kept for {days} days."),
+            None => "DM activity records will be kept forever.".to_string(),
+        }
+    })
+    .await
+}
+
+/// List members recently flagged for unusual DM activity.
+#[poise::command(
+    rename = "list",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::view_logs"
+)]
+pub async fn dm_activity_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let records = ctx
+        .data()
+        .database
+        .get_dm_activity(guild_id, LIST_LIMIT)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let description = if records.is_empty() {
+        "No members have been flagged.".to_string()
+    } else {
+        records
+            .iter()
+            .map(|record| {
+                let until = record.until.map_or_else(
+                    || "unknown".to_string(),
+                    |until| format!("<t:{}:R>", until.unix_timestamp()),
+                );
+                format!(
+                    "<@{}>: flagged {} time{}, last until {until}",
+                    record.user_id,
+                    record.count,
+                    if record.count == 1 { "" } else { "s" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Unusual DM Activity")
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Applies `update` to the current settings and saves them, replying with the returned message.
+async fn update_dm_activity_settings(
+    ctx: Context<'_>,
+    update: impl FnOnce(&mut DmActivitySettings) -> String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .dm_activity_settings;
+
+    let content = update(&mut settings);
+    guild_handler
+        .set_dm_activity_settings(guild_id, settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [dm_activity()]
+}
@@ -0,0 +1,180 @@
+use mothy_core::database_models::GuildFeatures;
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+use crate::{Context, Error};
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum Feature {
+    #[name = "Expression Tracking"]
+    ExpressionTracking,
+    #[name = "DM Activity"]
+    DmActivity,
+    #[name = "Autoresponse"]
+    Autoresponse,
+    #[name = "Automoderation"]
+    Automoderation,
+    #[name = "Sticky Roles"]
+    StickyRoles,
+    #[name = "Colour of the Day"]
+    ColourOfTheDay,
+}
+
+impl Feature {
+    const ALL: [Feature; 6] = [
+        Feature::ExpressionTracking,
+        Feature::DmActivity,
+        Feature::Autoresponse,
+        Feature::Automoderation,
+        Feature::StickyRoles,
+        Feature::ColourOfTheDay,
+    ];
+
+    fn flag(self) -> GuildFeatures {
+        match self {
+            Feature::ExpressionTracking => GuildFeatures::EXPRESSION_TRACKING,
+            Feature::DmActivity => GuildFeatures::DM_ACTIVITY,
+            Feature::Autoresponse => GuildFeatures::AUTORESPONSE,
+            Feature::Automoderation => GuildFeatures::AUTOMODERATION,
+            Feature::StickyRoles => GuildFeatures::STICKY_ROLES,
+            Feature::ColourOfTheDay => GuildFeatures::COLOUR_OF_THE_DAY,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Feature::ExpressionTracking => "Record which emotes and stickers get used",
+            Feature::DmActivity => "Announce members flagged for unusual DM activity",
+            Feature::Autoresponse => "Respond to messages matching regex triggers",
+            Feature::Automoderation => "Run the link and spambot filters",
+            Feature::StickyRoles => "Give roles back to members that leave and rejoin",
+            Feature::ColourOfTheDay => "Rotate role colours on a schedule",
+        }
+    }
+}
+
+/// Choose which of Mothy's subsystems run in this server.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands("features_list", "features_enable", "features_disable"),
+    subcommand_required
+)]
+pub async fn features(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List every feature and whether it is enabled.
+#[poise::command(
+    rename = "list",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn features_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let features = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .features;
+
+    let description = Feature::ALL
+        .iter()
+        .map(|feature| {
+            let state = if features.contains(feature.flag()) {
+                "✅"
+            } else {
+                "❌"
+            };
+            format!("{state} **{}**: {}", feature.name(), feature.description())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Features")
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Enable a feature.
+#[poise::command(
+    rename = "enable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn features_enable(
+    ctx: Context<'_>,
+    #[description = "The feature to enable"] feature: Feature,
+) -> Result<(), Error> {
+    set_feature(ctx, feature, true).await
+}
+
+/// Disable a feature.
+#[poise::command(
+    rename = "disable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn features_disable(
+    ctx: Context<'_>,
+    #[description = "The feature to disable"] feature: Feature,
+) -> Result<(), Error> {
+    set_feature(ctx, feature, false).await
+}
+
+async fn set_feature(ctx: Context<'_>, feature: Feature, enabled: bool) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut features = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .features;
+
+    if features.contains(feature.flag()) == enabled {
+        ctx.say(format!(
+            "**{}** is already {}.",
+            feature.name(),
+            if enabled { "enabled" } else { "disabled" }
+        ))
+        .await?;
+        return Ok(());
+    }
+    features.set(feature.flag(), enabled);
+
+    guild_handler
+        .set_features(guild_id, features)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!(
+        "**{}** is now {}.",
+        feature.name(),
+        if enabled { "enabled" } else { "disabled" }
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [features()]
+}
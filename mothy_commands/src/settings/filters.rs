@@ -0,0 +1,393 @@
+use crate::{Context, Error, checks};
+use mothy_core::database_models::{
+    FilterEscalation, FilterSettings, GuildFeatures, MAX_FILTER_TIMEOUT_MINUTES,
+};
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum Escalation {
+    #[name = "None"]
+    None,
+    #[name = "Kick"]
+    Kick,
+    #[name = "Ban"]
+    Ban,
+}
+
+impl From<Escalation> for FilterEscalation {
+    fn from(escalation: Escalation) -> Self {
+        match escalation {
+            Escalation::None => FilterEscalation::None,
+            Escalation::Kick => FilterEscalation::Kick,
+            Escalation::Ban => FilterEscalation::Ban,
+        }
+    }
+}
+
+/// Configure the link and spambot filters for this server.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    check = "checks::manage_filters",
+    subcommands(
+        "filters_enable",
+        "filters_disable",
+        "filters_bypass_add",
+        "filters_bypass_remove",
+        "filters_punishments",
+        "filters_strikes_clear",
+        "filters_show"
+    ),
+    subcommand_required
+)]
+pub async fn filters(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Enable the message filters.
+#[poise::command(
+    rename = "enable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn filters_enable(ctx: Context<'_>) -> Result<(), Error> {
+    set_filters_enabled(ctx, true).await
+}
+
+/// Disable the message filters.
+#[poise::command(
+    rename = "disable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn filters_disable(ctx: Context<'_>) -> Result<(), Error> {
+    set_filters_enabled(ctx, false).await
+}
+
+async fn set_filters_enabled(ctx: Context<'_>, is_enabled: bool) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let guild_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let mut filter_settings = guild_settings.filter_settings.clone();
+    filter_settings.is_enabled = is_enabled;
+
+    guild_handler
+        .set_filter_settings(guild_id, &filter_settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let content = if !is_enabled {
+        "Filters disabled."
+    } else if guild_settings
+        .features
+        .contains(GuildFeatures::AUTOMODERATION)
+    {
+        "Filters enabled."
+    } else {
+        "Filters enabled, they will start running once the Automoderation feature is enabled with `features enable`."
+    };
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// Let a role bypass the message filters.
+#[poise::command(
+    rename = "bypass-add",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn filters_bypass_add(
+    ctx: Context<'_>,
+    #[description = "The role that should bypass the filters"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut filter_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .filter_settings
+        .clone();
+
+    if filter_settings.bypass_roles.contains(&role.id) {
+        ctx.say(format!("<@&{}> already bypasses the filters.", role.id))
+            .await?;
+        return Ok(());
+    }
+    filter_settings.bypass_roles.push(role.id);
+
+    guild_handler
+        .set_filter_settings(guild_id, &filter_settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!("<@&{}> now bypasses the filters.", role.id))
+        .await?;
+
+    Ok(())
+}
+
+/// Stop a role from bypassing the message filters.
+#[poise::command(
+    rename = "bypass-remove",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn filters_bypass_remove(
+    ctx: Context<'_>,
+    #[description = "The role that should no longer bypass the filters"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut filter_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .filter_settings
+        .clone();
+
+    let previous_len = filter_settings.bypass_roles.len();
+    filter_settings.bypass_roles.retain(|id| *id != role.id);
+    if filter_settings.bypass_roles.len() == previous_len {
+        ctx.say(format!("<@&{}> does not bypass the filters.", role.id))
+            .await?;
+        return Ok(());
+    }
+
+    guild_handler
+        .set_filter_settings(guild_id, &filter_settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!("<@&{}> no longer bypasses the filters.", role.id))
+        .await?;
+
+    Ok(())
+}
+
+/// Set how people whose messages are filtered are punished, leave an option out to keep it.
+///
+/// Timeouts need the Moderate Members permission, kicks and bans need Kick or Ban Members.
+#[poise::command(
+    rename = "punishments",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn filters_punishments(
+    ctx: Context<'_>,
+    #[description = "Minutes to time out for each filtered message, 0 to not time out"]
+    #[max = 40320]
+    timeout_minutes: Option<u32>,
+    #[description = "What happens at the strike limit"] escalation: Option<Escalation>,
+    #[description = "Filtered messages within the window before escalating"]
+    #[min = 1]
+    #[max = 100]
+    strikes: Option<u32>,
+    #[description = "Minutes strikes are counted for, from the first one"]
+    #[min = 1]
+    #[max = 525600]
+    window_minutes: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut filter_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .filter_settings
+        .clone();
+
+    if let Some(timeout_minutes) = timeout_minutes {
+        filter_settings.timeout_minutes = timeout_minutes.min(MAX_FILTER_TIMEOUT_MINUTES);
+    }
+    if let Some(escalation) = escalation {
+        filter_settings.escalation = escalation.into();
+    }
+    if let Some(strikes) = strikes {
+        filter_settings.escalation_strikes = strikes.clamp(1, 100);
+    }
+    if let Some(window_minutes) = window_minutes {
+        filter_settings.strike_window_minutes = window_minutes.clamp(1, 525_600);
+    }
+
+    // setting up a punishment takes the Discord permission to hand it out yourself, changing
+    // when escalation happens counts as setting it up.
+    let mut required = serenity::Permissions::empty();
+    if timeout_minutes.is_some_and(|minutes| minutes > 0) {
+        required |= serenity::Permissions::MODERATE_MEMBERS;
+    }
+    if escalation.is_some() || strikes.is_some() || window_minutes.is_some() {
+        required |= match filter_settings.escalation {
+            FilterEscalation::None => serenity::Permissions::empty(),
+            FilterEscalation::Kick => serenity::Permissions::KICK_MEMBERS,
+            FilterEscalation::Ban => serenity::Permissions::BAN_MEMBERS,
+        };
+    }
+    let permissions = checks::author_roles_and_permissions(ctx)
+        .await
+        .map(|(_, permissions)| permissions)
+        .unwrap_or_default();
+    if !permissions.contains(required) {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(format!(
+                    "You need the {} permission to set up this punishment.",
+                    required - permissions
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    guild_handler
+        .set_filter_settings(guild_id, &filter_settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!(
+        "Filtered messages are now punished with: {}",
+        punishments_description(&filter_settings)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Forget someone's filter strikes.
+#[poise::command(
+    rename = "strikes-clear",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn filters_strikes_clear(
+    ctx: Context<'_>,
+    #[description = "The user whose strikes should be cleared"] user: serenity::User,
+) -> Result<(), Error> {
+    let cleared = ctx
+        .data()
+        .database
+        .clear_filter_strikes(ctx.guild_id().unwrap(), user.id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(if cleared {
+        format!("Cleared <@{}>'s strikes.", user.id)
+    } else {
+        format!("<@{}> has no strikes.", user.id)
+    })
+    .await?;
+
+    Ok(())
+}
+
+fn punishments_description(settings: &FilterSettings) -> String {
+    let timeout = match settings.timeout_minutes {
+        0 => "no timeout".to_string(),
+        minutes => format!("a {minutes} minute timeout"),
+    };
+    let escalation = match settings.escalation {
+        FilterEscalation::None => return format!("{timeout} for each filtered message."),
+        FilterEscalation::Kick => "kick",
+        FilterEscalation::Ban => "ban",
+    };
+    format!(
+        "{timeout} for each filtered message, and a {escalation} after {} within {} minutes.",
+        settings.escalation_strikes, settings.strike_window_minutes
+    )
+}
+
+/// Show the filter settings.
+#[poise::command(
+    rename = "show",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn filters_show(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let filter_settings = &guild_settings.filter_settings;
+
+    let bypass_roles = if filter_settings.bypass_roles.is_empty() {
+        "None".to_string()
+    } else {
+        filter_settings
+            .bypass_roles
+            .iter()
+            .map(|id| format!("<@&{id}>"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Filters")
+        .field(
+            "Enabled",
+            if filter_settings.is_enabled {
+                "Yes"
+            } else {
+                "No"
+            },
+            true,
+        )
+        .field(
+            "Automoderation Feature",
+            if guild_settings
+                .features
+                .contains(GuildFeatures::AUTOMODERATION)
+            {
+                "Enabled"
+            } else {
+                "Disabled"
+            },
+            true,
+        )
+        .field("Bypass Roles", bypass_roles, true)
+        .field(
+            "Punishments",
+            punishments_description(filter_settings),
+            false,
+        );
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [filters()]
+}
@@ -0,0 +1,297 @@
+use mothy_core::regex_filters::{compile_pattern, domain_to_pattern};
+use poise::serenity_prelude as serenity;
+
+use crate::{Context, Error, checks};
+
+const SEARCH_LIMIT: i64 = 25;
+const MAX_IMPORT_SIZE: u32 = 1024 * 1024;
+
+/// Manage the links the filters delete.
+#[poise::command(
+    rename = "link-blacklist",
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    check = "checks::manage_filters",
+    subcommands(
+        "link_blacklist_add",
+        "link_blacklist_except",
+        "link_blacklist_remove",
+        "link_blacklist_search",
+        "link_blacklist_import",
+        "link_blacklist_reload"
+    ),
+    subcommand_required
+)]
+pub async fn link_blacklist(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Delete messages linking to a domain, or matching a pattern.
+#[poise::command(
+    rename = "add",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn link_blacklist_add(
+    ctx: Context<'_>,
+    #[description = "A domain like `dlscord.gift`, or a regex matched against links"] entry: String,
+    #[description = "Add it for every server (bot owners only)"] global: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = entry_guild(ctx, global).await? else {
+        return Ok(());
+    };
+    add_entries(ctx, guild_id, vec![entry], false).await
+}
+
+/// Let links through in this server even if the blacklist matches them.
+#[poise::command(
+    rename = "except",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn link_blacklist_except(
+    ctx: Context<'_>,
+    #[description = "A domain like `example.com`, or a regex matched against links"] entry: String,
+) -> Result<(), Error> {
+    add_entries(ctx, ctx.guild_id(), vec![entry], true).await
+}
+
+/// Remove a blacklist entry or exception.
+#[poise::command(
+    rename = "remove",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn link_blacklist_remove(
+    ctx: Context<'_>,
+    #[description = "The entry as it was added, see `link-blacklist search`"] entry: String,
+    #[description = "Remove an exception instead of an entry"] exception: Option<bool>,
+    #[description = "Remove it for every server (bot owners only)"] global: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = entry_guild(ctx, global).await? else {
+        return Ok(());
+    };
+    let data = ctx.data();
+    let is_exception = exception.unwrap_or(false);
+
+    let pattern = domain_to_pattern(&entry);
+    let removed = data
+        .database
+        .delete_link_blacklist_entry(guild_id, &pattern, is_exception)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if removed {
+        data.regex_filters
+            .reload(&data.database)
+            .await
+            .map_err(Error::from_anyhow)?;
+    }
+
+    let list = list_name(is_exception);
+    ctx.say(if removed {
+        format!("Removed `{}`.", escape(&pattern))
+    } else {
+        format!("`{}` isn't in the {list}.", escape(&pattern))
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Find blacklist entries containing some text.
+#[poise::command(
+    rename = "search",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn link_blacklist_search(
+    ctx: Context<'_>,
+    #[description = "Text the entries contain"]
+    #[rest]
+    query: String,
+) -> Result<(), Error> {
+    let entries = ctx
+        .data()
+        .database
+        .search_link_blacklist(ctx.guild_id().unwrap(), query.trim(), SEARCH_LIMIT)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let description = if entries.is_empty() {
+        "No matching entries.".to_string()
+    } else {
+        entries
+            .iter()
+            .map(|entry| {
+                let kind = match (entry.guild_id, entry.is_exception) {
+                    (None, _) => "Global",
+                    (Some(_), false) => "Server",
+                    (Some(_), true) => "Exception",
+                };
+                format!("{kind}: `{}`", escape(&entry.pattern))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Link Blacklist")
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Add every domain in a text file, one per line.
+#[poise::command(
+    rename = "import",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_filters"
+)]
+pub async fn link_blacklist_import(
+    ctx: Context<'_>,
+    #[description = "A text file of domains or patterns, lines starting with # are skipped"]
+    file: serenity::Attachment,
+    #[description = "Add them for every server (bot owners only)"] global: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = entry_guild(ctx, global).await? else {
+        return Ok(());
+    };
+
+    if file.size > MAX_IMPORT_SIZE {
+        ctx.say("That file is too big, split it into files under 1MiB.")
+            .await?;
+        return Ok(());
+    }
+
+    let contents = file.download().await?;
+    let entries = String::from_utf8_lossy(&contents)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect();
+
+    add_entries(ctx, guild_id, entries, false).await
+}
+
+/// Reload the blacklist from the database.
+#[poise::command(
+    rename = "reload",
+    prefix_command,
+    hide_in_help,
+    guild_only,
+    owners_only
+)]
+pub async fn link_blacklist_reload(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.data();
+    data.regex_filters
+        .reload(&data.database)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say("Reloaded the link blacklist.").await?;
+    Ok(())
+}
+
+/// The guild entries are changed for, `None` for the global blacklist.
+///
+/// Replies and returns `Ok(None)` if a non-owner tries to change the global blacklist.
+async fn entry_guild(
+    ctx: Context<'_>,
+    global: Option<bool>,
+) -> Result<Option<Option<serenity::GuildId>>, Error> {
+    if !global.unwrap_or(false) {
+        return Ok(Some(ctx.guild_id()));
+    }
+
+    if ctx.framework().options().owners.contains(&ctx.author().id) {
+        Ok(Some(None))
+    } else {
+        checks::deny(ctx).await?;
+        Ok(None)
+    }
+}
+
+async fn add_entries(
+    ctx: Context<'_>,
+    guild_id: Option<serenity::GuildId>,
+    entries: Vec<String>,
+    is_exception: bool,
+) -> Result<(), Error> {
+    let mut patterns = Vec::with_capacity(entries.len());
+    let mut invalid = Vec::new();
+    for entry in entries {
+        let pattern = domain_to_pattern(&entry);
+        match compile_pattern(&pattern) {
+            Ok(_) => patterns.push(pattern),
+            Err(_) => invalid.push(pattern),
+        }
+    }
+
+    let data = ctx.data();
+    let added = if patterns.is_empty() {
+        0
+    } else {
+        let added = data
+            .database
+            .insert_link_blacklist_entries(guild_id, &patterns, is_exception, ctx.author().id)
+            .await
+            .map_err(Error::from_anyhow)?;
+        data.regex_filters
+            .reload(&data.database)
+            .await
+            .map_err(Error::from_anyhow)?;
+        added
+    };
+
+    let list = list_name(is_exception);
+    let mut content = match (patterns.as_slice(), added) {
+        ([pattern], 1) => format!("Added `{}`.", escape(pattern)),
+        ([pattern], _) => format!("`{}` is already in the {list}.", escape(pattern)),
+        (patterns, added) => format!(
+            "Added {added} entries, {} were already in the {list}.",
+            patterns.len() as u64 - added
+        ),
+    };
+    if !invalid.is_empty() {
+        content.push_str(&format!(
+            "\nSkipped {} invalid patterns, like `{}`.",
+            invalid.len(),
+            escape(&invalid[0])
+        ));
+    }
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// What entries of a kind are called in replies.
+fn list_name(is_exception: bool) -> &'static str {
+    if is_exception {
+        "exceptions"
+    } else {
+        "blacklist"
+    }
+}
+
+fn escape(pattern: &str) -> String {
+    pattern.replace('`', "")
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [link_blacklist()]
+}
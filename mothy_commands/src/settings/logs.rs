@@ -0,0 +1,123 @@
+use crate::{Context, Error};
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+#[derive(poise::ChoiceParameter, Clone, Copy)]
+pub enum LogKind {
+    #[name = "Join"]
+    Join,
+    #[name = "Blacklist"]
+    Blacklist,
+    #[name = "Voice"]
+    Voice,
+}
+
+/// Configure where Mothy posts its logs in this server.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands("logs_set", "logs_show"),
+    subcommand_required
+)]
+pub async fn logs(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set or clear a log channel.
+#[poise::command(
+    rename = "set",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn logs_set(
+    ctx: Context<'_>,
+    #[description = "Which log to configure"] kind: LogKind,
+    #[description = "The channel to log to, leave empty to disable"] channel: Option<
+        serenity::GuildChannel,
+    >,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut log_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .log_settings;
+
+    let channel_id = channel.map(|c| c.id.widen());
+    match kind {
+        LogKind::Join => log_settings.join_logs_channel_id = channel_id,
+        LogKind::Blacklist => log_settings.blacklist_logs_channel_id = channel_id,
+        LogKind::Voice => log_settings.voice_logs_channel_id = channel_id,
+    }
+
+    guild_handler
+        .set_log_settings(guild_id, log_settings)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let content = match channel_id {
+        Some(channel_id) => format!("{} logs will now be sent to <#{channel_id}>.", kind.name()),
+        None => format!("{} logs have been disabled.", kind.name()),
+    };
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// Show the configured log channels.
+#[poise::command(
+    rename = "show",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn logs_show(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let log_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .log_settings;
+
+    let format_channel = |channel_id: Option<serenity::GenericChannelId>| {
+        channel_id.map_or_else(|| "Not set".to_string(), |id| format!("<#{id}>"))
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Log Channels")
+        .field(
+            "Join",
+            format_channel(log_settings.join_logs_channel_id),
+            true,
+        )
+        .field(
+            "Blacklist",
+            format_channel(log_settings.blacklist_logs_channel_id),
+            true,
+        )
+        .field(
+            "Voice",
+            format_channel(log_settings.voice_logs_channel_id),
+            true,
+        );
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [logs()]
+}
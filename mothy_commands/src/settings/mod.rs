@@ -0,0 +1,28 @@
+pub mod automod;
+pub mod autoresponse;
+pub mod cotd;
+pub mod dm_activity;
+pub mod features;
+pub mod filters;
+pub mod link_blacklist;
+pub mod logs;
+pub mod mod_roles;
+pub mod prefix;
+pub mod sticky_roles;
+
+#[must_use]
+pub fn commands() -> Vec<crate::Command> {
+    logs::commands()
+        .into_iter()
+        .chain(filters::commands())
+        .chain(link_blacklist::commands())
+        .chain(prefix::commands())
+        .chain(features::commands())
+        .chain(sticky_roles::commands())
+        .chain(cotd::commands())
+        .chain(dm_activity::commands())
+        .chain(autoresponse::commands())
+        .chain(mod_roles::commands())
+        .chain(automod::commands())
+        .collect()
+}
@@ -0,0 +1,245 @@
+use mothy_core::database_models::{ModRole, ModRolePermissions};
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+use crate::{Context, Error};
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum ModPermission {
+    #[name = "Manage autoresponse triggers"]
+    ManageTriggers,
+    #[name = "Manage sticky roles"]
+    ManageStickyRoles,
+    #[name = "Manage colour of the day"]
+    ManageCotd,
+    #[name = "Manage filters"]
+    ManageFilters,
+    #[name = "Bypass filters"]
+    BypassFilters,
+    #[name = "View logs"]
+    ViewLogs,
+}
+
+impl ModPermission {
+    const ALL: [ModPermission; 6] = [
+        ModPermission::ManageTriggers,
+        ModPermission::ManageStickyRoles,
+        ModPermission::ManageCotd,
+        ModPermission::ManageFilters,
+        ModPermission::BypassFilters,
+        ModPermission::ViewLogs,
+    ];
+}
+
+impl From<ModPermission> for ModRolePermissions {
+    fn from(permission: ModPermission) -> Self {
+        match permission {
+            ModPermission::ManageTriggers => ModRolePermissions::MANAGE_TRIGGERS,
+            ModPermission::ManageStickyRoles => ModRolePermissions::MANAGE_STICKY_ROLES,
+            ModPermission::ManageCotd => ModRolePermissions::MANAGE_COTD,
+            ModPermission::ManageFilters => ModRolePermissions::MANAGE_FILTERS,
+            ModPermission::BypassFilters => ModRolePermissions::BYPASS_FILTERS,
+            ModPermission::ViewLogs => ModRolePermissions::VIEW_LOGS,
+        }
+    }
+}
+
+/// Give roles access to parts of the bot without Discord permissions.
+#[poise::command(
+    rename = "mod-roles",
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands(
+        "mod_roles_list",
+        "mod_roles_grant",
+        "mod_roles_revoke",
+        "mod_roles_remove"
+    ),
+    subcommand_required
+)]
+pub async fn mod_roles(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List the mod roles and what they can do.
+#[poise::command(
+    rename = "list",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn mod_roles_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let description = if guild_settings.mod_roles.is_empty() {
+        "No mod roles.".to_string()
+    } else {
+        guild_settings
+            .mod_roles
+            .iter()
+            .map(|mod_role| {
+                format!(
+                    "<@&{}>: {}",
+                    mod_role.role_id,
+                    permission_names(mod_role.permissions)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Mod Roles")
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Let a role do something.
+#[poise::command(
+    rename = "grant",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn mod_roles_grant(
+    ctx: Context<'_>,
+    #[description = "The role to grant the permission to"] role: serenity::Role,
+    #[description = "The permission to grant"] permission: ModPermission,
+) -> Result<(), Error> {
+    update_mod_role(ctx, role.id, |permissions| {
+        *permissions |= permission.into();
+        format!(
+            "<@&{}> can now {}.",
+            role.id,
+            permission.name().to_lowercase()
+        )
+    })
+    .await
+}
+
+/// Stop a role from doing something.
+#[poise::command(
+    rename = "revoke",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn mod_roles_revoke(
+    ctx: Context<'_>,
+    #[description = "The role to revoke the permission from"] role: serenity::Role,
+    #[description = "The permission to revoke"] permission: ModPermission,
+) -> Result<(), Error> {
+    update_mod_role(ctx, role.id, |permissions| {
+        *permissions -= permission.into();
+        format!(
+            "<@&{}> can no longer {}.",
+            role.id,
+            permission.name().to_lowercase()
+        )
+    })
+    .await
+}
+
+/// Stop a role from being a mod role, removing its automod overrides too.
+#[poise::command(
+    rename = "remove",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn mod_roles_remove(
+    ctx: Context<'_>,
+    #[description = "The role to remove"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let removed = ctx
+        .data()
+        .database
+        .guild_handler
+        .delete_mod_role(guild_id, role.id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(if removed {
+        format!("<@&{}> is no longer a mod role.", role.id)
+    } else {
+        format!("<@&{}> is not a mod role.", role.id)
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Applies `update` to a role's permissions and saves them, replying with the returned message.
+///
+/// A role left with no permissions is kept, automod overrides may still refer to it.
+/// `mod-roles remove` drops it.
+async fn update_mod_role(
+    ctx: Context<'_>,
+    role_id: serenity::RoleId,
+    update: impl FnOnce(&mut ModRolePermissions) -> String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut permissions = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .mod_roles
+        .iter()
+        .find(|mod_role| mod_role.role_id == role_id)
+        .map(|mod_role| mod_role.permissions)
+        .unwrap_or_default();
+
+    let content = update(&mut permissions);
+    guild_handler
+        .set_mod_role(
+            guild_id,
+            ModRole {
+                role_id,
+                permissions,
+            },
+        )
+        .await
+        .map_err(Error::from_anyhow)?;
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+fn permission_names(permissions: ModRolePermissions) -> String {
+    let names: Vec<&str> = ModPermission::ALL
+        .into_iter()
+        .filter(|permission| permissions.contains((*permission).into()))
+        .map(|permission| permission.name())
+        .collect();
+
+    if names.is_empty() {
+        "Nothing".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [mod_roles()]
+}
@@ -0,0 +1,169 @@
+use arrayvec::ArrayString;
+
+use crate::{Context, Error, STOCK_PREFIX};
+
+/// Manage the prefix Mothy responds to in this server.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    subcommands("prefix_set", "prefix_reset", "prefix_show", "prefix_stock"),
+    subcommand_required
+)]
+pub async fn prefix(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set a custom prefix for this server.
+#[poise::command(
+    rename = "set",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn prefix_set(
+    ctx: Context<'_>,
+    #[description = "The new prefix, up to 6 bytes long"] prefix: String,
+) -> Result<(), Error> {
+    let prefix = match parse_prefix(&prefix) {
+        Ok(prefix) => prefix,
+        Err(reason) => {
+            ctx.say(reason).await?;
+            return Ok(());
+        }
+    };
+
+    let guild_id = ctx.guild_id().unwrap();
+    ctx.data()
+        .database
+        .guild_handler
+        .set_prefix(guild_id, Some(prefix))
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!("Prefix set to `{prefix}`.")).await?;
+
+    Ok(())
+}
+
+/// Remove the custom prefix and re-enable the stock prefix.
+#[poise::command(
+    rename = "reset",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn prefix_reset(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    ctx.data()
+        .database
+        .guild_handler
+        .reset_prefix(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(format!("Prefix reset to `{STOCK_PREFIX}`."))
+        .await?;
+
+    Ok(())
+}
+
+/// Show the prefixes Mothy responds to in this server.
+#[poise::command(rename = "show", slash_command, prefix_command, guild_only)]
+pub async fn prefix_show(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let mut prefixes = vec![];
+    if let Some(prefix) = guild_settings.prefix {
+        prefixes.push(format!("`{prefix}`"));
+    }
+    if !guild_settings.stock_prefix_disabled() {
+        prefixes.push(format!("`{STOCK_PREFIX}`"));
+    }
+    prefixes.push("a mention".to_string());
+
+    ctx.say(format!("Prefixes in this server: {}", prefixes.join(", ")))
+        .await?;
+
+    Ok(())
+}
+
+/// Enable or disable the stock `m` prefix for this server.
+#[poise::command(
+    rename = "stock",
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn prefix_stock(
+    ctx: Context<'_>,
+    #[description = "Whether the stock prefix should work in this server"] enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let guild_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if !enabled && guild_settings.prefix.is_none() {
+        ctx.say("Set a custom prefix before disabling the stock prefix.")
+            .await?;
+        return Ok(());
+    }
+
+    guild_handler
+        .set_stock_prefix_disabled(guild_id, !enabled)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    ctx.say(if enabled {
+        format!("The stock prefix `{STOCK_PREFIX}` is enabled.")
+    } else {
+        format!("The stock prefix `{STOCK_PREFIX}` is disabled.")
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Validates a prefix against the `guilds.prefix` column, which is `VARCHAR(6)`.
+fn parse_prefix(prefix: &str) -> Result<ArrayString<6>, &'static str> {
+    if prefix.is_empty() {
+        return Err("The prefix can't be empty.");
+    }
+    if prefix.chars().any(char::is_whitespace) {
+        return Err("The prefix can't contain whitespace.");
+    }
+
+    ArrayString::from(prefix).map_err(|_| "The prefix can't be longer than 6 bytes.")
+}
+
+#[test]
+fn test_parse_prefix() {
+    assert!(parse_prefix("!").is_ok());
+    assert!(parse_prefix("mothy!").is_ok());
+    assert!(parse_prefix("").is_err());
+    assert!(parse_prefix("mothy!!").is_err());
+    assert!(parse_prefix("a b").is_err());
+    // 3 bytes per character, so only two fit
+    assert!(parse_prefix("蛾蛾").is_ok());
+    assert!(parse_prefix("蛾蛾蛾").is_err());
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [prefix()]
+}
@@ -0,0 +1,282 @@
+use mothy_core::database_models::{GuildFeatures, StickyRoleMode, StickyRoleSettings};
+use poise::{ChoiceParameter, serenity_prelude as serenity};
+
+use crate::{Context, Error, checks};
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum Mode {
+    #[name = "All roles"]
+    All,
+    #[name = "Allowlist"]
+    Allowlist,
+    #[name = "Denylist"]
+    Denylist,
+}
+
+impl From<Mode> for StickyRoleMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::All => StickyRoleMode::None,
+            Mode::Allowlist => StickyRoleMode::Allowlist,
+            Mode::Denylist => StickyRoleMode::Denylist,
+        }
+    }
+}
+
+#[derive(ChoiceParameter, Clone, Copy)]
+pub enum RoleList {
+    #[name = "Allowlist"]
+    Allowlist,
+    #[name = "Denylist"]
+    Denylist,
+}
+
+/// Give members their roles back when they leave and rejoin.
+#[poise::command(
+    rename = "sticky-roles",
+    slash_command,
+    prefix_command,
+    category = "Settings",
+    guild_only,
+    check = "checks::manage_sticky_roles",
+    subcommands(
+        "sticky_roles_show",
+        "sticky_roles_enable",
+        "sticky_roles_disable",
+        "sticky_roles_mode",
+        "sticky_roles_add",
+        "sticky_roles_remove"
+    ),
+    subcommand_required
+)]
+pub async fn sticky_roles(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show the sticky role settings.
+#[poise::command(
+    rename = "show",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_sticky_roles"
+)]
+pub async fn sticky_roles_show(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_settings = ctx
+        .data()
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    let sticky_settings = &guild_settings.sticky_role_settings;
+
+    let format_roles = |roles: &[serenity::RoleId]| {
+        if roles.is_empty() {
+            "None".to_string()
+        } else {
+            roles
+                .iter()
+                .map(|id| format!("<@&{id}>"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+
+    let mode = match sticky_settings.mode {
+        StickyRoleMode::None => Mode::All,
+        StickyRoleMode::Allowlist => Mode::Allowlist,
+        StickyRoleMode::Denylist => Mode::Denylist,
+    };
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Sticky Roles")
+        .field(
+            "Enabled",
+            if sticky_settings.is_enabled {
+                "Yes"
+            } else {
+                "No"
+            },
+            true,
+        )
+        .field(
+            "Sticky Roles Feature",
+            if guild_settings
+                .features
+                .contains(GuildFeatures::STICKY_ROLES)
+            {
+                "Enabled"
+            } else {
+                "Disabled"
+            },
+            true,
+        )
+        .field("Mode", mode.name(), true)
+        .field(
+            "Allowlist",
+            format_roles(&sticky_settings.allowlist_roles),
+            false,
+        )
+        .field(
+            "Denylist",
+            format_roles(&sticky_settings.denylist_roles),
+            false,
+        );
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Enable sticky roles.
+#[poise::command(
+    rename = "enable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_sticky_roles"
+)]
+pub async fn sticky_roles_enable(ctx: Context<'_>) -> Result<(), Error> {
+    update_sticky_settings(ctx, |settings| {
+        settings.is_enabled = true;
+        Ok("Sticky roles enabled.".to_string())
+    })
+    .await
+}
+
+/// Disable sticky roles.
+#[poise::command(
+    rename = "disable",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_sticky_roles"
+)]
+pub async fn sticky_roles_disable(ctx: Context<'_>) -> Result<(), Error> {
+    update_sticky_settings(ctx, |settings| {
+        settings.is_enabled = false;
+        Ok("Sticky roles disabled.".to_string())
+    })
+    .await
+}
+
+/// Choose which roles stick.
+#[poise::command(
+    rename = "mode",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_sticky_roles"
+)]
+pub async fn sticky_roles_mode(
+    ctx: Context<'_>,
+    #[description = "Which roles should be given back on rejoin"] mode: Mode,
+) -> Result<(), Error> {
+    update_sticky_settings(ctx, |settings| {
+        settings.mode = mode.into();
+        Ok(format!("Sticky roles mode set to **{}**.", mode.name()))
+    })
+    .await
+}
+
+/// Add a role to the allowlist or denylist.
+#[poise::command(
+    rename = "add",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_sticky_roles"
+)]
+pub async fn sticky_roles_add(
+    ctx: Context<'_>,
+    #[description = "The list to add the role to"] list: RoleList,
+    #[description = "The role to add"] role: serenity::Role,
+) -> Result<(), Error> {
+    update_sticky_settings(ctx, |settings| {
+        let roles = match list {
+            RoleList::Allowlist => &mut settings.allowlist_roles,
+            RoleList::Denylist => &mut settings.denylist_roles,
+        };
+
+        if roles.contains(&role.id) {
+            return Err(format!(
+                "<@&{}> is already in the {}.",
+                role.id,
+                list.name()
+            ));
+        }
+        roles.push(role.id);
+
+        Ok(format!("Added <@&{}> to the {}.", role.id, list.name()))
+    })
+    .await
+}
+
+/// Remove a role from the allowlist or denylist.
+#[poise::command(
+    rename = "remove",
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "checks::manage_sticky_roles"
+)]
+pub async fn sticky_roles_remove(
+    ctx: Context<'_>,
+    #[description = "The list to remove the role from"] list: RoleList,
+    #[description = "The role to remove"] role: serenity::Role,
+) -> Result<(), Error> {
+    update_sticky_settings(ctx, |settings| {
+        let roles = match list {
+            RoleList::Allowlist => &mut settings.allowlist_roles,
+            RoleList::Denylist => &mut settings.denylist_roles,
+        };
+
+        let previous_len = roles.len();
+        roles.retain(|id| *id != role.id);
+        if roles.len() == previous_len {
+            return Err(format!("<@&{}> is not in the {}.", role.id, list.name()));
+        }
+
+        Ok(format!("Removed <@&{}> from the {}.", role.id, list.name()))
+    })
+    .await
+}
+
+/// Applies `update` to the current settings and saves them, replying with the returned message.
+///
+/// Returning `Err` from `update` replies with the message without saving anything.
+async fn update_sticky_settings(
+    ctx: Context<'_>,
+    update: impl FnOnce(&mut StickyRoleSettings) -> Result<String, String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let data = ctx.data();
+    let guild_handler = &data.database.guild_handler;
+
+    let mut sticky_settings = guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?
+        .sticky_role_settings
+        .clone();
+
+    let content = match update(&mut sticky_settings) {
+        Ok(content) => {
+            guild_handler
+                .set_sticky_role_settings(guild_id, &sticky_settings)
+                .await
+                .map_err(Error::from_anyhow)?;
+            content
+        }
+        Err(content) => content,
+    };
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [sticky_roles()]
+}
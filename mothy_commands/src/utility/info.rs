@@ -89,7 +89,6 @@ pub async fn roleinfo(
     let permissions_list = formatted_permissions.join(", ");
 
     let timestamp = role.id.created_at().format("%Y/%m/%d %H:%M");
-    println!("{:?}", role.unicode_emoji);
 
     let embed = serenity::CreateEmbed::default()
         .field("ID", role_id, true)
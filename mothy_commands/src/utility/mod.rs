@@ -3,6 +3,7 @@ pub mod colour;
 pub mod info;
 pub mod patch_fix;
 pub mod random;
+pub mod regex_test;
 pub mod udev;
 pub mod urban;
 
@@ -17,6 +18,7 @@ pub fn commands() -> Vec<crate::Command> {
             .chain(avatar::commands())
             .chain(patch_fix::commands())
             .chain(udev::commands())
+            .chain(regex_test::commands())
             .collect()
     }
 }
@@ -0,0 +1,106 @@
+use crate::{Context, Error};
+
+use mothy_core::database_models::Pattern;
+use poise::serenity_prelude::CreateEmbed;
+
+/// Tries a pattern against some text, with the same limits autoresponse triggers use.
+#[poise::command(
+    rename = "regex-test",
+    slash_command,
+    prefix_command,
+    category = "Utility",
+    install_context = "Guild|User",
+    interaction_context = "Guild|BotDm|PrivateChannel"
+)]
+pub async fn regex_test(
+    ctx: Context<'_>,
+    #[description = "The regex to test"] pattern: String,
+    #[description = "The text to match against"] text: String,
+    #[description = "Use fancy regex, which supports lookaround and backreferences"] fancy: Option<
+        bool,
+    >,
+) -> Result<(), Error> {
+    let pattern = match Pattern::new(&pattern, fancy.unwrap_or_default()) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            ctx.say(format!("Invalid pattern: {e}")).await?;
+            return Ok(());
+        }
+    };
+
+    let description = match pattern.captures(&text) {
+        Ok(Some(groups)) => describe_groups(&groups),
+        Ok(None) => "No match.".to_string(),
+        Err(e) => format!("Matching failed: {e}"),
+    };
+
+    let embed = CreateEmbed::default()
+        .title("Regex Test")
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Lists what each group captured, cutting long captures and leaving out the groups that
+/// don't fit in an embed description.
+fn describe_groups(groups: &[Option<&str>]) -> String {
+    const MAX_LEN: usize = 4096;
+    const MAX_GROUP_LEN: usize = 1000;
+    // room for the "…and N more" line.
+    const RESERVED: usize = 32;
+
+    let mut description = String::new();
+    let mut len = 0;
+    for (i, group) in groups.iter().enumerate() {
+        let label = if i == 0 {
+            "Match".to_string()
+        } else {
+            format!("Group {i}")
+        };
+        let line = match group {
+            Some(text) => {
+                let mut text = text.replace('`', "\\`");
+                if text.chars().count() > MAX_GROUP_LEN {
+                    text = text.chars().take(MAX_GROUP_LEN - 1).collect();
+                    text.push('…');
+                }
+                format!("**{label}:** `{text}`")
+            }
+            None => format!("**{label}:** did not participate"),
+        };
+
+        let line_len = line.chars().count() + 1;
+        let reserved = if i + 1 == groups.len() { 0 } else { RESERVED };
+        if len + line_len + reserved > MAX_LEN {
+            description.push_str(&format!("\n…and {} more", groups.len() - i));
+            break;
+        }
+        if !description.is_empty() {
+            description.push('\n');
+        }
+        description.push_str(&line);
+        len += line_len;
+    }
+    description
+}
+
+#[must_use]
+pub fn commands() -> [crate::Command; 1] {
+    [regex_test()]
+}
+
+#[test]
+fn test_describe_groups() {
+    assert_eq!(
+        describe_groups(&[Some("a`b"), None]),
+        "**Match:** `a\\`b`\n**Group 1:** did not participate"
+    );
+
+    let long = "a".repeat(1500);
+    let groups = vec![Some(long.as_str()); 10];
+    let description = describe_groups(&groups);
+    assert!(description.chars().count() <= 4096);
+    assert!(description.contains(&format!("`{}…`", "a".repeat(999))));
+    assert!(description.ends_with("\n…and 6 more"));
+}
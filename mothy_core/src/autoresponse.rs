@@ -0,0 +1,200 @@
+use serenity::all::{Cache, GenericChannelId, GuildId, Permissions, RoleId};
+
+use crate::database_models::{
+    AutoresponseSettings, GlobalRegexDenylistChannel, GuildSettings, RegexTrigger, TriggerContext,
+};
+
+/// The channel itself, then the channel a thread belongs to, then the category above that.
+///
+/// Only what the cache knows about is included, so an uncached channel yields just itself.
+#[must_use]
+pub fn channel_ancestry(
+    cache: &Cache,
+    guild_id: GuildId,
+    channel_id: GenericChannelId,
+) -> Vec<GenericChannelId> {
+    let mut ancestry = vec![channel_id];
+    let Some(guild_cache) = cache.guild(guild_id) else {
+        return ancestry;
+    };
+
+    let mut current = channel_id.expect_channel();
+    if let Some(thread) = guild_cache
+        .threads
+        .iter()
+        .find(|t| t.id == channel_id.get())
+    {
+        ancestry.push(thread.parent_id.widen());
+        current = thread.parent_id;
+    }
+
+    if let Some(category) = guild_cache
+        .channels
+        .get(&current)
+        .and_then(|channel| channel.parent_id)
+    {
+        ancestry.push(category.widen());
+    }
+
+    ancestry
+}
+
+/// Whether a channel scope covers a message, `ancestry` starts with the message's own channel
+/// followed by its parent channel (for threads) and category.
+#[must_use]
+pub fn scope_covers(
+    scope: GenericChannelId,
+    is_recursive: bool,
+    ancestry: &[GenericChannelId],
+) -> bool {
+    if is_recursive {
+        ancestry.contains(&scope)
+    } else {
+        ancestry.first() == Some(&scope)
+    }
+}
+
+#[must_use]
+pub fn is_denied(denylist: &[GlobalRegexDenylistChannel], ancestry: &[GenericChannelId]) -> bool {
+    denylist
+        .iter()
+        .any(|entry| scope_covers(entry.channel_id, entry.is_recursive, ancestry))
+}
+
+/// Whether a trigger is enabled, listens to `context` and is scoped to the message's channel.
+#[must_use]
+pub fn trigger_applies(
+    trigger: &RegexTrigger,
+    context: TriggerContext,
+    ancestry: &[GenericChannelId],
+) -> bool {
+    trigger.is_enabled()
+        && trigger.trigger_context.intersects(context)
+        && trigger
+            .channel_id
+            .is_none_or(|scope| scope_covers(scope, trigger.is_recursive(), ancestry))
+}
+
+/// Finds the first trigger matching `text` in the given channel, unless the channel is denied.
+#[must_use]
+pub fn find_trigger<'a>(
+    guild_settings: &'a GuildSettings,
+    context: TriggerContext,
+    ancestry: &[GenericChannelId],
+    text: &str,
+) -> Option<&'a RegexTrigger> {
+    if is_denied(&guild_settings.regex_denylist, ancestry) {
+        return None;
+    }
+
+    guild_settings
+        .regex_triggers
+        .iter()
+        .filter(|trigger| trigger_applies(trigger, context, ancestry))
+        .find(|trigger| trigger.pattern.is_match(text))
+}
+
+/// Whether a member may create, edit or delete triggers.
+///
+/// Server managers always can, otherwise the member needs an allowed role or every permission of
+/// one of the allowed permission sets.
+#[must_use]
+pub fn can_manage_triggers(
+    settings: &AutoresponseSettings,
+    roles: &[RoleId],
+    permissions: Permissions,
+) -> bool {
+    permissions.intersects(Permissions::ADMINISTRATOR | Permissions::MANAGE_GUILD)
+        || roles
+            .iter()
+            .any(|role| settings.allowed_roles.contains(role))
+        || settings
+            .allowed_permissions
+            .iter()
+            .any(|required| !required.is_empty() && permissions.contains(*required))
+}
+
+#[cfg(test)]
+fn test_trigger(pattern: &str, channel_id: Option<u64>, is_recursive: bool) -> RegexTrigger {
+    use crate::database_models::{Pattern, TriggerMetadata};
+
+    RegexTrigger::new(
+        Pattern::Simple(regex::Regex::new(pattern).unwrap()),
+        channel_id.map(GenericChannelId::new),
+        TriggerContext::TEXT,
+        TriggerMetadata {
+            text: Some(pattern.to_string()),
+        },
+        is_recursive,
+    )
+}
+
+#[test]
+fn test_find_trigger() {
+    let thread = GenericChannelId::new(3);
+    let channel = GenericChannelId::new(2);
+    let category = GenericChannelId::new(1);
+    let ancestry = [thread, channel, category];
+
+    let mut guild_settings = GuildSettings {
+        regex_triggers: vec![
+            test_trigger("moth", Some(1), false),
+            test_trigger("moth", Some(1), true),
+            test_trigger("lamp", None, true),
+        ],
+        ..Default::default()
+    };
+
+    let found = find_trigger(&guild_settings, TriggerContext::TEXT, &ancestry, "a moth");
+    assert!(found.is_some_and(|t| t.is_recursive()));
+    assert!(find_trigger(&guild_settings, TriggerContext::OCR, &ancestry, "a moth").is_none());
+    assert!(find_trigger(&guild_settings, TriggerContext::TEXT, &ancestry, "a lamp").is_some());
+
+    guild_settings.regex_denylist = vec![GlobalRegexDenylistChannel {
+        channel_id: channel,
+        is_recursive: true,
+    }];
+    assert!(find_trigger(&guild_settings, TriggerContext::TEXT, &ancestry, "a lamp").is_none());
+    // the denylist entry only covers what's below the channel.
+    assert!(find_trigger(&guild_settings, TriggerContext::TEXT, &[category], "a lamp").is_some());
+}
+
+#[test]
+fn test_can_manage_triggers() {
+    let helper = RoleId::new(1);
+    let settings = AutoresponseSettings {
+        allowed_roles: vec![helper],
+        allowed_permissions: vec![
+            Permissions::MANAGE_MESSAGES | Permissions::MODERATE_MEMBERS,
+            Permissions::empty(),
+        ],
+        ..Default::default()
+    };
+
+    assert!(can_manage_triggers(
+        &settings,
+        &[],
+        Permissions::MANAGE_GUILD
+    ));
+    assert!(can_manage_triggers(
+        &settings,
+        &[helper],
+        Permissions::empty()
+    ));
+    assert!(can_manage_triggers(
+        &settings,
+        &[],
+        Permissions::MANAGE_MESSAGES | Permissions::MODERATE_MEMBERS | Permissions::SEND_MESSAGES
+    ));
+    // holding part of a set isn't enough, and an empty set doesn't allow everyone.
+    assert!(!can_manage_triggers(
+        &settings,
+        &[],
+        Permissions::MANAGE_MESSAGES
+    ));
+    assert!(!can_manage_triggers(
+        &settings,
+        &[RoleId::new(2)],
+        Permissions::SEND_MESSAGES
+    ));
+}
@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use rand::{Rng, seq::IndexedRandom};
+use resvg::{tiny_skia, usvg};
+use serenity::all::{
+    Colour, Context, CreateAttachment, EditRole, GuildId, RoleColours, RoleId, UserId,
+};
+use sqlx::types::time::{OffsetDateTime, PrimitiveDateTime, Time};
+
+use crate::{
+    database::Database,
+    database_models::{
+        ColourMode, CotdHistoryEntry, CotdRoleSettings, GuildFeatures, IconPairingMode,
+    },
+};
+
+/// How many of the most recent colours a random pick tries not to repeat.
+const RANDOM_HISTORY_AVOIDANCE: usize = 7;
+/// Role icons are shown at a small size, Discord recommends at least 64x64.
+const ICON_SIZE: u32 = 128;
+
+/// The directory SVG icons are loaded from, `MOTHY_COTD_ICONS_DIR` or the bundled ones.
+fn icons_dir() -> String {
+    std::env::var("MOTHY_COTD_ICONS_DIR").unwrap_or_else(|_| "assets/emojis/svgs".to_string())
+}
+
+/// Where an SVG icon is read from, `None` unless `icon` is a plain file name, so stored icons can
+/// never point outside `icons_dir()`.
+#[must_use]
+pub fn svg_icon_path(icon: &str) -> Option<PathBuf> {
+    let is_file_name =
+        !icon.is_empty() && !icon.starts_with('.') && !icon.contains(['/', '\\', ':']);
+    is_file_name.then(|| Path::new(&icons_dir()).join(icon))
+}
+
+/// When scheduled rotations last failed, so a broken role is retried the next day rather than
+/// every time the schedule is checked.
+static FAILED_ROTATIONS: LazyLock<Mutex<HashMap<(GuildId, RoleId), OffsetDateTime>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub struct CotdRotation {
+    pub colours: RoleColours,
+    pub icon: Option<String>,
+}
+
+/// Rotates a colour of the day role to its next colour (and icon) and records it in the history.
+pub async fn rotate(
+    ctx: &Context,
+    database: &Database,
+    guild_id: GuildId,
+    settings: &CotdRoleSettings,
+    assigned_by: Option<UserId>,
+) -> anyhow::Result<CotdRotation> {
+    let history = database
+        .get_cotd_history(guild_id, settings.role_id, RANDOM_HISTORY_AVOIDANCE as i64)
+        .await?;
+
+    let Some(colour_index) = pick_colour_index(settings, &history) else {
+        anyhow::bail!("role {} has no colours configured", settings.role_id);
+    };
+    let colours = settings.colours[colour_index].clone();
+    let icon = pick_icon(settings, colour_index).cloned();
+
+    let mut builder = EditRole::new()
+        .colours(colours.clone())
+        .audit_log_reason("Colour of the day");
+
+    if settings.suffix_enabled() {
+        let current_name = ctx.cache.guild(guild_id).and_then(|guild| {
+            guild
+                .roles
+                .get(&settings.role_id)
+                .map(|r| r.name.to_string())
+        });
+
+        if let Some(current_name) = current_name {
+            builder = builder.name(format!(
+                "{} (#{})",
+                strip_colour_suffix(&current_name),
+                colours.primary_colour.hex()
+            ));
+        }
+    }
+
+    let attachment;
+    match &icon {
+        Some(icon) if icon.ends_with(".svg") => {
+            let Some(path) = svg_icon_path(icon) else {
+                anyhow::bail!("icon `{icon}` is not a file name");
+            };
+            let svg = tokio::fs::read_to_string(path).await?;
+            let svg = match settings.svg_target_colour {
+                Some(target) => recolour_svg(&svg, target, colours.primary_colour.0),
+                None => svg,
+            };
+            attachment = CreateAttachment::bytes(render_svg_png(&svg)?, "icon.png");
+            builder = builder.icon(Some(&attachment));
+        }
+        Some(emoji) => builder = builder.unicode_emoji(Some(emoji.clone())),
+        None => {}
+    }
+
+    guild_id
+        .edit_role(&ctx.http, settings.role_id, builder)
+        .await?;
+
+    database
+        .insert_cotd_history(
+            guild_id,
+            settings.role_id,
+            colours.primary_colour.0,
+            icon.as_deref(),
+            assigned_by,
+        )
+        .await?;
+
+    Ok(CotdRotation { colours, icon })
+}
+
+/// Rotates every enabled role whose rotation time has passed today, in guilds with the feature on.
+pub async fn rotate_due_roles(ctx: &Context, database: &Database) -> anyhow::Result<()> {
+    let now = OffsetDateTime::now_utc();
+
+    for schedule in database.get_cotd_schedules().await? {
+        let key = (schedule.guild_id, schedule.role_id);
+        let last_failed = FAILED_ROTATIONS.lock().unwrap().get(&key).copied();
+        let last_attempt = schedule.last_assigned.max(last_failed);
+        if !is_rotation_due(schedule.rotation_time, last_attempt, now) {
+            continue;
+        }
+
+        let guild_settings = match database.guild_handler.get(schedule.guild_id).await {
+            Ok(guild_settings) => guild_settings,
+            Err(e) => {
+                tracing::error!(
+                    guild_id = schedule.guild_id.get(),
+                    error = ?e,
+                    "Failed to load guild settings for colour of the day"
+                );
+                continue;
+            }
+        };
+        if !guild_settings
+            .features
+            .contains(GuildFeatures::COLOUR_OF_THE_DAY)
+        {
+            continue;
+        }
+
+        let Some(settings) = guild_settings
+            .cotd_settings
+            .iter()
+            .find(|s| s.role_id == schedule.role_id)
+        else {
+            continue;
+        };
+
+        // one broken role (deleted, above the bot, bad icon) shouldn't stop the others.
+        if let Err(e) = rotate(ctx, database, schedule.guild_id, settings, None).await {
+            FAILED_ROTATIONS.lock().unwrap().insert(key, now);
+            tracing::error!(
+                guild_id = schedule.guild_id.get(),
+                role_id = schedule.role_id.get(),
+                error = %e,
+                "Failed to rotate colour of the day role, retrying at tomorrow's rotation"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the rotation for today (UTC) has passed without a colour being assigned since.
+#[must_use]
+pub fn is_rotation_due(
+    rotation_time: Time,
+    last_assigned: Option<OffsetDateTime>,
+    now: OffsetDateTime,
+) -> bool {
+    let todays_rotation = PrimitiveDateTime::new(now.date(), rotation_time).assume_utc();
+    if now < todays_rotation {
+        return false;
+    }
+
+    last_assigned.is_none_or(|last| last < todays_rotation)
+}
+
+/// Picks the index of the next colour, `history` is ordered from most to least recent.
+#[must_use]
+pub fn pick_colour_index(
+    settings: &CotdRoleSettings,
+    history: &[CotdHistoryEntry],
+) -> Option<usize> {
+    if settings.colours.is_empty() {
+        return None;
+    }
+
+    let position_of = |colour: u32| {
+        settings
+            .colours
+            .iter()
+            .position(|c| c.primary_colour.0 == colour)
+    };
+
+    match settings.colour_mode {
+        ColourMode::Static => {
+            let last_index = history.first().and_then(|entry| position_of(entry.colour));
+            Some(last_index.map_or(0, |i| (i + 1) % settings.colours.len()))
+        }
+        ColourMode::Random => {
+            // never avoid every colour, there has to be something left to pick.
+            let avoid = RANDOM_HISTORY_AVOIDANCE.min(settings.colours.len() - 1);
+            let recent: Vec<u32> = history.iter().take(avoid).map(|e| e.colour).collect();
+
+            let candidates: Vec<usize> = (0..settings.colours.len())
+                .filter(|i| !recent.contains(&settings.colours[*i].primary_colour.0))
+                .collect();
+
+            let mut rng = rand::rng();
+            candidates
+                .choose(&mut rng)
+                .copied()
+                .or_else(|| Some(rng.random_range(0..settings.colours.len())))
+        }
+    }
+}
+
+#[must_use]
+pub fn pick_icon(settings: &CotdRoleSettings, colour_index: usize) -> Option<&String> {
+    if settings.icons.is_empty() {
+        return None;
+    }
+
+    match settings.icon_pairing_mode {
+        IconPairingMode::Paired => settings.icons.get(colour_index % settings.icons.len()),
+        IconPairingMode::Random => settings.icons.choose(&mut rand::rng()),
+    }
+}
+
+/// Replaces every occurrence of `from` in the SVG source with `to`, both as `#rrggbb`.
+#[must_use]
+pub fn recolour_svg(svg: &str, from: u32, to: u32) -> String {
+    let from = format!("#{:06x}", from & 0xFF_FFFF);
+    let to = format!("#{:06x}", to & 0xFF_FFFF);
+
+    let pattern = regex::RegexBuilder::new(&regex::escape(&from))
+        .case_insensitive(true)
+        .build()
+        .expect("escaped hex colour is a valid regex");
+
+    pattern.replace_all(svg, to.as_str()).into_owned()
+}
+
+/// Rasterises an SVG into a square PNG suitable for a role icon.
+pub fn render_svg_png(svg: &str) -> anyhow::Result<Vec<u8>> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default())?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(ICON_SIZE, ICON_SIZE)
+        .ok_or_else(|| anyhow::anyhow!("failed to allocate icon pixmap"))?;
+
+    let size = tree.size();
+    let scale = (ICON_SIZE as f32 / size.width()).min(ICON_SIZE as f32 / size.height());
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    Ok(pixmap.encode_png()?)
+}
+
+/// Removes a ` (#rrggbb)` suffix added by a previous rotation.
+#[must_use]
+pub fn strip_colour_suffix(name: &str) -> &str {
+    if let Some((base, suffix)) = name.rsplit_once(" (#")
+        && let Some(hex) = suffix.strip_suffix(')')
+        && hex.len() == 6
+        && hex.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return base;
+    }
+
+    name
+}
+
+/// Parses a `HH:MM` UTC rotation time.
+pub fn parse_rotation_time(time: &str) -> Result<Time, &'static str> {
+    let error = "Rotation times should be written as `HH:MM` in UTC.";
+
+    let (hour, minute) = time.trim().split_once(':').ok_or(error)?;
+    let hour = hour.parse().map_err(|_| error)?;
+    let minute = minute.parse().map_err(|_| error)?;
+
+    Time::from_hms(hour, minute, 0).map_err(|_| error)
+}
+
+#[must_use]
+pub fn format_rotation_time(time: Time) -> String {
+    format!("{:02}:{:02} UTC", time.hour(), time.minute())
+}
+
+/// Parses a hex colour, with or without a leading `#`.
+pub fn parse_hex_colour(colour: &str) -> Result<u32, String> {
+    let hex = colour.trim_start_matches('#');
+    if hex.len() != 6 {
+        return Err(format!("`{colour}` is not a 6 digit hex colour."));
+    }
+
+    u32::from_str_radix(hex, 16).map_err(|_| format!("`{colour}` is not a 6 digit hex colour."))
+}
+
+/// Parses a list of colours separated by spaces or commas.
+///
+/// Each entry may be a gradient of up to three colours joined with `/`, e.g. `#ff0000/#0000ff`.
+pub fn parse_colours(colours: &str) -> Result<Vec<RoleColours>, String> {
+    colours
+        .split([' ', ','])
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let parts = entry
+                .split('/')
+                .map(parse_hex_colour)
+                .collect::<Result<Vec<_>, _>>()?;
+            if parts.len() > 3 {
+                return Err(format!("`{entry}` has more than three colours."));
+            }
+
+            Ok(RoleColours {
+                primary_colour: Colour::new(parts[0]),
+                secondary_colour: parts.get(1).map(|c| Colour::new(*c)),
+                tertiary_colour: parts.get(2).map(|c| Colour::new(*c)),
+            })
+        })
+        .collect()
+}
+
+#[must_use]
+pub fn format_colours(colours: &RoleColours) -> String {
+    [
+        Some(colours.primary_colour),
+        colours.secondary_colour,
+        colours.tertiary_colour,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|c: Colour| format!("#{}", c.hex()))
+    .collect::<Vec<_>>()
+    .join(" → ")
+}
+
+#[must_use]
+pub fn role_mention(role_id: RoleId) -> String {
+    format!("<@&{role_id}>")
+}
+
+#[test]
+fn test_is_rotation_due() {
+    use sqlx::types::time::macros::datetime;
+    use sqlx::types::time::macros::time;
+
+    let rotation_time = time!(12:00);
+    let now = datetime!(2025-10-18 12:30 UTC);
+
+    assert!(is_rotation_due(rotation_time, None, now));
+    assert!(is_rotation_due(
+        rotation_time,
+        Some(datetime!(2025-10-17 12:00 UTC)),
+        now
+    ));
+    assert!(!is_rotation_due(
+        rotation_time,
+        Some(datetime!(2025-10-18 12:00 UTC)),
+        now
+    ));
+    // before today's rotation time nothing is due, even if yesterday was missed.
+    assert!(!is_rotation_due(
+        rotation_time,
+        Some(datetime!(2025-10-16 12:00 UTC)),
+        datetime!(2025-10-18 11:59 UTC)
+    ));
+}
+
+#[test]
+fn test_svg_icon_path() {
+    assert_eq!(
+        svg_icon_path("moth.svg"),
+        Some(Path::new(&icons_dir()).join("moth.svg"))
+    );
+    assert_eq!(svg_icon_path("../../secret.svg"), None);
+    assert_eq!(svg_icon_path("icons/moth.svg"), None);
+    assert_eq!(svg_icon_path("..\\moth.svg"), None);
+    assert_eq!(svg_icon_path(".."), None);
+    assert_eq!(svg_icon_path(""), None);
+}
+
+#[test]
+fn test_recolour_svg() {
+    let svg = r##"<path fill="#FF00aa"/><path stroke="#ff00AA"/><path fill="#000000"/>"##;
+    assert_eq!(
+        recolour_svg(svg, 0xff00aa, 0x123456),
+        r##"<path fill="#123456"/><path stroke="#123456"/><path fill="#000000"/>"##
+    );
+}
+
+#[test]
+fn test_parse_colours() {
+    let colours = parse_colours("#ff0000, 00ff00/#0000ff").unwrap();
+    assert_eq!(colours.len(), 2);
+    assert_eq!(colours[0].primary_colour.0, 0xff0000);
+    assert_eq!(colours[0].secondary_colour, None);
+    assert_eq!(colours[1].primary_colour.0, 0x00ff00);
+    assert_eq!(colours[1].secondary_colour.map(|c| c.0), Some(0x0000ff));
+
+    assert!(parse_colours("#fff").is_err());
+    assert!(parse_colours("red").is_err());
+    assert!(parse_colours("#000000/#000000/#000000/#000000").is_err());
+}
+
+#[test]
+fn test_parse_rotation_time() {
+    assert_eq!(
+        parse_rotation_time("09:30"),
+        Ok(Time::from_hms(9, 30, 0).unwrap())
+    );
+    assert!(parse_rotation_time("24:00").is_err());
+    assert!(parse_rotation_time("noon").is_err());
+}
+
+#[test]
+fn test_strip_colour_suffix() {
+    assert_eq!(strip_colour_suffix("Colour (#ff00aa)"), "Colour");
+    assert_eq!(strip_colour_suffix("Colour"), "Colour");
+    assert_eq!(strip_colour_suffix("Colour (#nothex)"), "Colour (#nothex)");
+}
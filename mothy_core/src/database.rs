@@ -1,19 +1,52 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use dashmap::DashMap;
-use serenity::all::GuildId;
+use serenity::all::{GuildId, Timestamp, UserId};
+use sqlx::types::time::OffsetDateTime;
 
 use crate::database_models::{
-    ColourMode, CotdRoleSettings, DmActivitySettings, GlobalRegexDenylistChannel, GuildFeatures,
-    GuildSettings, GuildSettingsGeneratedFlags, IconPairingMode, ModRole, RawCotdRoleSettings,
-    RawDmActivitySettings, RawGlobalRegexDenylistChannel, RawModRole, RawRegexTrigger,
+    AutomodRuleOverrides, AutoresponseSettings, ColourMode, CotdRoleSettings, DmActivitySettings,
+    FilterEscalation, FilterSettings, GlobalRegexDenylistChannel, GuildFeatures, GuildSettings,
+    GuildSettingsGeneratedFlags, IconPairingMode, InvalidRegexTrigger, LogSettings, ModRole,
+    RawAutomodRuleOverride, RawAutoresponseSettings, RawCotdRoleSettings, RawDmActivitySettings,
+    RawFilterSettings, RawGlobalRegexDenylistChannel, RawLogSettings, RawModRole, RawRegexTrigger,
     RawStickyRoleSettings, RegexTrigger, StickyRoleMode, StickyRoleSettings, TriggerContext,
     truncate_convert,
 };
 
+mod cotd;
+mod dm_activity;
+mod executed_commands;
+mod expression_stats;
+mod expressions;
+mod filter_strikes;
+mod guild_writes;
+mod link_blacklist;
+mod ocr;
+mod sticky_roles;
+mod users;
+
+pub use cotd::CotdSchedule;
+pub use dm_activity::DmActivityRecord;
+pub use executed_commands::{CommandFailure, CommandType, CommandUsage, ExecutedCommand};
+pub use expression_stats::{ExpressionCount, ExpressionStatsFilter};
+pub use expressions::{EmoteUsage, StickerUsage};
+pub use link_blacklist::LinkBlacklistEntry;
+
+fn to_offset_date_time(timestamp: Timestamp) -> OffsetDateTime {
+    OffsetDateTime::from_unix_timestamp(timestamp.unix_timestamp())
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+}
+
+fn to_timestamp(date_time: OffsetDateTime) -> Option<Timestamp> {
+    Timestamp::from_unix_timestamp(date_time.unix_timestamp()).ok()
+}
+
 pub struct Database {
-    /* pool: sqlx::PgPool, */
+    pool: sqlx::PgPool,
     pub guild_handler: GuildHandler,
+    pending_expressions: Mutex<expressions::PendingExpressions>,
+    bot_banned_users: DashMap<UserId, bool>,
 }
 
 impl Database {
@@ -36,8 +69,10 @@ impl Database {
             .expect("Could not run migrations.");
 
         Self {
-            guild_handler: GuildHandler::new(pool),
-            /*             pool, */
+            guild_handler: GuildHandler::new(pool.clone()),
+            pending_expressions: Mutex::default(),
+            bot_banned_users: DashMap::new(),
+            pool,
         }
     }
 }
@@ -65,9 +100,35 @@ impl GuildHandler {
         Ok(guild_or_default)
     }
 
+    /// Reloads a guild's settings from the database and swaps them into the cache.
+    ///
+    /// If loading fails the cached value is evicted instead, so the next `get` retries.
+    pub async fn refresh(&self, guild_id: GuildId) -> anyhow::Result<Arc<GuildSettings>> {
+        match self.get_(guild_id).await {
+            Ok(settings) => {
+                let settings = Arc::new(settings);
+                self.cache.insert(guild_id, settings.clone());
+                Ok(settings)
+            }
+            Err(e) => {
+                self.cache.remove(&guild_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Drops a guild's cached settings without reloading them.
+    pub fn invalidate(&self, guild_id: GuildId) {
+        self.cache.remove(&guild_id);
+    }
+
     async fn get_(&self, guild_id: GuildId) -> anyhow::Result<GuildSettings> {
         let Ok(main_guild) = sqlx::query!(
-            "SELECT prefix, feature_flags, rejoined, banned FROM guilds WHERE guild_id = $1",
+            r#"
+            SELECT prefix, feature_flags, rejoined, banned, stock_prefix_disabled
+            FROM guilds
+            WHERE guild_id = $1
+            "#,
             guild_id.get() as i64
         )
         .fetch_one(&self.pool)
@@ -76,31 +137,51 @@ impl GuildHandler {
             return Ok(GuildSettings::default());
         };
 
-        let (dm_activity, regex_triggers, regex_denylist, sticky, cotd, mod_roles) = tokio::join!(
+        let (
+            dm_activity,
+            regex_triggers,
+            regex_denylist,
+            sticky,
+            cotd,
+            mod_roles,
+            log_settings,
+            filter_settings,
+            autoresponse_settings,
+            automod_rule_overrides,
+        ) = tokio::join!(
             get_dm_activity_settings(&self.pool, guild_id),
             get_regex_triggers_for_guild(&self.pool, guild_id),
             get_regex_denylists(&self.pool, guild_id),
             get_sticky_role_settings(&self.pool, guild_id),
             get_cotd_role_settings_for_guild(&self.pool, guild_id),
-            get_mod_roles(&self.pool, guild_id)
+            get_mod_roles(&self.pool, guild_id),
+            get_log_settings(&self.pool, guild_id),
+            get_filter_settings(&self.pool, guild_id),
+            get_autoresponse_settings(&self.pool, guild_id),
+            get_automod_rule_overrides(&self.pool, guild_id)
         );
 
+        let (regex_triggers, invalid_regex_triggers) = regex_triggers?;
         let mut settings = GuildSettings {
             prefix: main_guild.prefix.map(truncate_convert),
             features: GuildFeatures::from_bits_truncate(main_guild.feature_flags as u8),
-            regex_triggers: regex_triggers?,
+            regex_triggers,
+            invalid_regex_triggers,
             regex_denylist: regex_denylist?,
             mod_roles: mod_roles?,
-            // TODO: implement
-            automod_rule_overrides: vec![],
+            automod_rule_overrides: automod_rule_overrides?,
             sticky_role_settings: sticky?.unwrap_or_default(),
             cotd_settings: cotd?,
             dm_activity_settings: dm_activity?.unwrap_or_default(),
+            log_settings: log_settings?.unwrap_or_default(),
+            filter_settings: filter_settings?.unwrap_or_default(),
+            autoresponse_settings: autoresponse_settings?.unwrap_or_default(),
             __generated_flags: GuildSettingsGeneratedFlags::empty(),
         };
 
         settings.set_banned(main_guild.banned);
         settings.set_rejoined(main_guild.rejoined);
+        settings.set_stock_prefix_disabled(main_guild.stock_prefix_disabled);
 
         Ok(settings)
     }
@@ -125,10 +206,12 @@ async fn get_dm_activity_settings(
     Ok(raw.map(DmActivitySettings::from))
 }
 
+/// Loads a guild's triggers, disabling any stored trigger that no longer compiles instead of
+/// failing the whole guild.
 async fn get_regex_triggers_for_guild(
     pool: &sqlx::PgPool,
     guild_id: GuildId,
-) -> anyhow::Result<Vec<RegexTrigger>> {
+) -> anyhow::Result<(Vec<RegexTrigger>, Vec<InvalidRegexTrigger>)> {
     let raws = sqlx::query_as!(
         RawRegexTrigger,
         r#"
@@ -145,7 +228,36 @@ async fn get_regex_triggers_for_guild(
     .fetch_all(pool)
     .await?;
 
-    Ok(raws.into_iter().map(RegexTrigger::from).collect())
+    let mut triggers = Vec::with_capacity(raws.len());
+    let mut invalid = Vec::new();
+    for raw in raws {
+        match RegexTrigger::try_from(raw) {
+            Ok(trigger) => triggers.push(trigger),
+            Err(trigger) => invalid.push(trigger),
+        }
+    }
+
+    let mut newly_invalid = Vec::new();
+    for trigger in invalid.iter().filter(|t| t.was_enabled) {
+        tracing::warn!(
+            guild_id = guild_id.get(),
+            trigger_id = trigger.id,
+            reason = %trigger.reason,
+            "Disabling invalid regex trigger"
+        );
+        newly_invalid.push(trigger.id as i64);
+    }
+
+    if !newly_invalid.is_empty() {
+        sqlx::query!(
+            "UPDATE regex_triggers SET is_enabled = FALSE WHERE id = ANY($1)",
+            &newly_invalid,
+        )
+        .execute(pool)
+        .await?;
+    }
+
+    Ok((triggers, invalid))
 }
 
 async fn get_sticky_role_settings(
@@ -236,3 +348,87 @@ async fn get_regex_denylists(
         .map(GlobalRegexDenylistChannel::from)
         .collect())
 }
+
+async fn get_log_settings(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> anyhow::Result<Option<LogSettings>> {
+    let raw = sqlx::query_as!(
+        RawLogSettings,
+        r#"
+        SELECT join_logs_channel_id, blacklist_logs_channel_id, voice_logs_channel_id
+        FROM log_settings
+        WHERE guild_id = $1
+        "#,
+        guild_id.get() as i64
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(raw.map(LogSettings::from))
+}
+
+async fn get_filter_settings(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> anyhow::Result<Option<FilterSettings>> {
+    let raw = sqlx::query_as!(
+        RawFilterSettings,
+        r#"
+        SELECT
+            bypass_roles,
+            is_enabled,
+            timeout_minutes,
+            escalation AS "escalation: FilterEscalation",
+            escalation_strikes,
+            strike_window_minutes
+        FROM filter_settings
+        WHERE guild_id = $1
+        "#,
+        guild_id.get() as i64
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(raw.map(FilterSettings::from))
+}
+
+async fn get_autoresponse_settings(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> anyhow::Result<Option<AutoresponseSettings>> {
+    let raw = sqlx::query_as!(
+        RawAutoresponseSettings,
+        r#"
+        SELECT allowed_roles, allowed_permissions, logging_channel_id, log_changes
+        FROM autoresponse_settings
+        WHERE guild_id = $1
+        "#,
+        guild_id.get() as i64
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(raw.map(AutoresponseSettings::from))
+}
+
+async fn get_automod_rule_overrides(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> anyhow::Result<Vec<AutomodRuleOverrides>> {
+    let raws = sqlx::query_as!(
+        RawAutomodRuleOverride,
+        r#"
+        SELECT o.rule_id, o.role_id, o.is_allowed
+        FROM automod_rule_overrides o
+        JOIN mod_roles m ON m.role_id = o.role_id
+        WHERE m.guild_id = $1
+        ORDER BY o.rule_id
+        "#,
+        guild_id.get() as i64,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(AutomodRuleOverrides::from_raw(raws))
+}
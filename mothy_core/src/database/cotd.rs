@@ -0,0 +1,99 @@
+use serenity::all::{GuildId, RoleId, UserId};
+use sqlx::types::time::{OffsetDateTime, Time};
+
+use super::Database;
+use crate::database_models::{CotdHistoryEntry, RawCotdHistoryEntry};
+
+/// An enabled colour of the day role and when it was last given a colour.
+pub struct CotdSchedule {
+    pub guild_id: GuildId,
+    pub role_id: RoleId,
+    pub rotation_time: Time,
+    pub last_assigned: Option<OffsetDateTime>,
+}
+
+impl Database {
+    /// Gets every enabled colour of the day role across all guilds that aren't banned.
+    pub async fn get_cotd_schedules(&self) -> anyhow::Result<Vec<CotdSchedule>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                s.guild_id,
+                s.role_id,
+                s.rotation_time,
+                (
+                    SELECT MAX(h.assigned_at)
+                    FROM cotd_colour_history h
+                    WHERE h.guild_id = s.guild_id AND h.role_id = s.role_id
+                ) AS last_assigned
+            FROM cotd_role_settings s
+            JOIN guilds g ON g.guild_id = s.guild_id
+            WHERE s.is_enabled AND NOT g.banned
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| CotdSchedule {
+                guild_id: GuildId::new(r.guild_id as u64),
+                role_id: RoleId::new(r.role_id as u64),
+                rotation_time: r.rotation_time,
+                last_assigned: r.last_assigned,
+            })
+            .collect())
+    }
+
+    /// Gets the most recent colours given to a role, newest first.
+    pub async fn get_cotd_history(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        limit: i64,
+    ) -> anyhow::Result<Vec<CotdHistoryEntry>> {
+        let raws = sqlx::query_as!(
+            RawCotdHistoryEntry,
+            r#"
+            SELECT colour, icon, assigned_at, assigned_by
+            FROM cotd_colour_history
+            WHERE guild_id = $1 AND role_id = $2
+            ORDER BY assigned_at DESC
+            LIMIT $3
+            "#,
+            guild_id.get() as i64,
+            role_id.get() as i64,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(raws.into_iter().map(CotdHistoryEntry::from).collect())
+    }
+
+    /// Records a colour being given to a role, `assigned_by` is `None` for scheduled rotations.
+    pub async fn insert_cotd_history(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        colour: u32,
+        icon: Option<&str>,
+        assigned_by: Option<UserId>,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO cotd_colour_history (guild_id, role_id, colour, icon, assigned_by)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            guild_id.get() as i64,
+            role_id.get() as i64,
+            colour as i32,
+            icon,
+            assigned_by.map(|id| id.get() as i64),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use serenity::all::{GuildId, Timestamp, UserId};
+
+use super::guild_writes::{insert_guild, insert_user};
+use super::{Database, to_offset_date_time, to_timestamp};
+
+/// A member's DM activity flags in one guild.
+pub struct DmActivityRecord {
+    pub user_id: UserId,
+    /// How many separate times the member has been flagged.
+    pub count: u16,
+    pub until: Option<Timestamp>,
+    pub last_announced: Option<Timestamp>,
+    /// Whether this flag is a different period to the one recorded before it.
+    pub new_period: bool,
+}
+
+impl Database {
+    /// Records that a member is flagged until `until`, counting it once per distinct period.
+    pub async fn record_dm_activity(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        until: Timestamp,
+    ) -> anyhow::Result<DmActivityRecord> {
+        let until = to_offset_date_time(until);
+
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+        insert_user(&mut transaction, user_id).await?;
+
+        let previous = sqlx::query!(
+            r#"
+            SELECT until, count, last_announced
+            FROM dm_activity
+            WHERE guild_id = $1 AND user_id = $2
+            FOR UPDATE
+            "#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        let (new_period, count, last_announced) = match previous {
+            Some(previous) => {
+                let new_period = previous.until != Some(until);
+                let count = previous.count.unwrap_or(0) + i16::from(new_period);
+                (new_period, count, previous.last_announced)
+            }
+            None => (true, 1, None),
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO dm_activity (guild_id, user_id, until, count)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id, user_id) DO UPDATE SET
+                until = EXCLUDED.until,
+                count = EXCLUDED.count
+            "#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            until,
+            count,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(DmActivityRecord {
+            user_id,
+            count: count as u16,
+            until: to_timestamp(until),
+            last_announced: last_announced.and_then(to_timestamp),
+            new_period,
+        })
+    }
+
+    pub async fn mark_dm_activity_announced(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE dm_activity SET last_announced = NOW()
+            WHERE guild_id = $1 AND user_id = $2
+            "#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gets the members flagged in a guild, most recently flagged first.
+    pub async fn get_dm_activity(
+        &self,
+        guild_id: GuildId,
+        limit: i64,
+    ) -> anyhow::Result<Vec<DmActivityRecord>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT user_id, until, count, last_announced
+            FROM dm_activity
+            WHERE guild_id = $1
+            ORDER BY until DESC NULLS LAST
+            LIMIT $2
+            "#,
+            guild_id.get() as i64,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| DmActivityRecord {
+                user_id: UserId::new(r.user_id as u64),
+                count: r.count.unwrap_or(0) as u16,
+                until: r.until.and_then(to_timestamp),
+                last_announced: r.last_announced.and_then(to_timestamp),
+                new_period: false,
+            })
+            .collect())
+    }
+
+    /// Deletes records whose flag ended longer ago than their guild's `retention_days`.
+    pub async fn purge_dm_activity(&self) -> anyhow::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM dm_activity d
+            USING dm_activity_settings s
+            WHERE d.guild_id = s.guild_id
+                AND s.retention_days IS NOT NULL
+                AND COALESCE(d.until, d.last_announced)
+                    < NOW() - make_interval(days => s.retention_days::INT)
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,160 @@
+use serenity::all::{GenericChannelId, GuildId, Timestamp, UserId};
+
+use super::guild_writes::{insert_guild, insert_user};
+use super::{Database, to_offset_date_time, to_timestamp};
+
+#[derive(Debug, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "commandtype")]
+#[sqlx(rename_all = "snake_case")]
+pub enum CommandType {
+    Prefix,
+    /// An edit to a message that wasn't a command before.
+    PrefixUntracked,
+    PrefixEdited,
+    Application,
+}
+
+/// One invocation of a command, successful or not.
+pub struct ExecutedCommand {
+    pub user_id: UserId,
+    pub channel_id: GenericChannelId,
+    pub guild_id: Option<GuildId>,
+    /// The qualified name, so subcommands include their parent.
+    pub command: String,
+    pub command_type: CommandType,
+    pub executed_at: Timestamp,
+    pub error_text: Option<String>,
+}
+
+pub struct CommandUsage {
+    pub command: String,
+    pub uses: u64,
+    pub failures: u64,
+    pub last_failure: Option<Timestamp>,
+    pub last_error: Option<String>,
+}
+
+impl CommandUsage {
+    /// The share of uses that failed, from 0 to 1.
+    #[must_use]
+    pub fn error_rate(&self) -> f64 {
+        if self.uses == 0 {
+            return 0.0;
+        }
+
+        self.failures as f64 / self.uses as f64
+    }
+}
+
+pub struct CommandFailure {
+    pub user_id: UserId,
+    pub guild_id: Option<GuildId>,
+    pub executed_at: Option<Timestamp>,
+    pub error_text: Option<String>,
+}
+
+impl Database {
+    pub async fn insert_executed_command(&self, executed: ExecutedCommand) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_user(&mut transaction, executed.user_id).await?;
+        if let Some(guild_id) = executed.guild_id {
+            insert_guild(&mut transaction, guild_id).await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO executed_commands (
+                user_id, channel_id, guild_id, command, command_type, executed_at,
+                executed_successfully, error_text
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            executed.user_id.get() as i64,
+            executed.channel_id.get() as i64,
+            executed.guild_id.map(|id| id.get() as i64),
+            executed.command,
+            executed.command_type as CommandType,
+            to_offset_date_time(executed.executed_at),
+            executed.error_text.is_none(),
+            executed.error_text,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Uses and failures of every command that has been run, most used first.
+    pub async fn get_command_usage(&self) -> anyhow::Result<Vec<CommandUsage>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                counts.command AS "command!",
+                counts.uses AS "uses!",
+                counts.failures AS "failures!",
+                last.executed_at AS "last_failure?",
+                last.error_text AS "last_error?"
+            FROM (
+                SELECT
+                    command,
+                    COUNT(*) AS uses,
+                    COUNT(*) FILTER (WHERE NOT executed_successfully) AS failures
+                FROM executed_commands
+                GROUP BY command
+            ) counts
+            LEFT JOIN (
+                SELECT DISTINCT ON (command) command, executed_at, error_text
+                FROM executed_commands
+                WHERE NOT executed_successfully
+                ORDER BY command, executed_at DESC
+            ) last ON last.command = counts.command
+            ORDER BY counts.uses DESC, counts.command
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CommandUsage {
+                command: row.command,
+                uses: row.uses as u64,
+                failures: row.failures as u64,
+                last_failure: row.last_failure.and_then(to_timestamp),
+                last_error: row.last_error,
+            })
+            .collect())
+    }
+
+    /// The most recent failures of one command, newest first.
+    pub async fn get_command_failures(
+        &self,
+        command: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<CommandFailure>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT user_id, guild_id, executed_at, error_text
+            FROM executed_commands
+            WHERE command = $1 AND NOT executed_successfully
+            ORDER BY executed_at DESC
+            LIMIT $2
+            "#,
+            command,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CommandFailure {
+                user_id: UserId::new(row.user_id as u64),
+                guild_id: row.guild_id.map(|id| GuildId::new(id as u64)),
+                executed_at: to_timestamp(row.executed_at),
+                error_text: row.error_text,
+            })
+            .collect())
+    }
+}
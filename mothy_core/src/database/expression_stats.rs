@@ -0,0 +1,108 @@
+use serenity::all::{GenericChannelId, GuildId, UserId};
+
+use super::Database;
+use crate::database_models::EmoteUsageType;
+
+/// Narrows expression statistics down, every `None` means no restriction.
+pub struct ExpressionStatsFilter {
+    pub guild_id: GuildId,
+    pub user_id: Option<UserId>,
+    pub channel_id: Option<GenericChannelId>,
+    pub window_days: Option<i32>,
+    /// Least used first instead of most used first.
+    pub ascending: bool,
+    pub limit: Option<i64>,
+}
+
+/// How often one emote or sticker was used, `id` is the Discord id.
+pub struct ExpressionCount {
+    pub id: u64,
+    pub name: String,
+    pub uses: i64,
+}
+
+impl Database {
+    pub async fn get_emote_stats(
+        &self,
+        filter: &ExpressionStatsFilter,
+        usage_type: Option<EmoteUsageType>,
+    ) -> anyhow::Result<Vec<ExpressionCount>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT e.discord_id AS "id!", e.emote_name AS name, COUNT(*) AS "uses!"
+            FROM emote_usage u
+            JOIN emotes e ON e.emote_id = u.emote_id
+            WHERE u.guild_id = $1
+                AND ($2::BIGINT IS NULL OR u.user_id = $2)
+                AND ($3::BIGINT IS NULL OR u.channel_id = $3)
+                AND ($4::INT IS NULL OR u.used_at >= NOW() - make_interval(days => $4))
+                AND ($5::EmoteUsageType IS NULL OR u.usage_type = $5)
+                AND e.discord_id IS NOT NULL
+            GROUP BY e.discord_id, e.emote_name
+            ORDER BY
+                CASE WHEN $6 THEN COUNT(*) END ASC,
+                CASE WHEN NOT $6 THEN COUNT(*) END DESC,
+                e.emote_name
+            LIMIT $7
+            "#,
+            filter.guild_id.get() as i64,
+            filter.user_id.map(|id| id.get() as i64),
+            filter.channel_id.map(|id| id.get() as i64),
+            filter.window_days,
+            usage_type as Option<EmoteUsageType>,
+            filter.ascending,
+            filter.limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| ExpressionCount {
+                id: r.id as u64,
+                name: r.name,
+                uses: r.uses,
+            })
+            .collect())
+    }
+
+    pub async fn get_sticker_stats(
+        &self,
+        filter: &ExpressionStatsFilter,
+    ) -> anyhow::Result<Vec<ExpressionCount>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT s.sticker_id AS id, s.sticker_name AS name, COUNT(*) AS "uses!"
+            FROM sticker_usage u
+            JOIN stickers s ON s.sticker_id = u.sticker_id
+            WHERE u.guild_id = $1
+                AND ($2::BIGINT IS NULL OR u.user_id = $2)
+                AND ($3::BIGINT IS NULL OR u.channel_id = $3)
+                AND ($4::INT IS NULL OR u.used_at >= NOW() - make_interval(days => $4))
+            GROUP BY s.sticker_id, s.sticker_name
+            ORDER BY
+                CASE WHEN $5 THEN COUNT(*) END ASC,
+                CASE WHEN NOT $5 THEN COUNT(*) END DESC,
+                s.sticker_name
+            LIMIT $6
+            "#,
+            filter.guild_id.get() as i64,
+            filter.user_id.map(|id| id.get() as i64),
+            filter.channel_id.map(|id| id.get() as i64),
+            filter.window_days,
+            filter.ascending,
+            filter.limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| ExpressionCount {
+                id: r.id as u64,
+                name: r.name,
+                uses: r.uses,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{HashMap, HashSet};
+
+use serenity::all::{EmojiId, GenericChannelId, GuildId, MessageId, StickerId, Timestamp, UserId};
+
+use super::{Database, to_offset_date_time};
+use crate::database_models::EmoteUsageType;
+
+/// A custom emote used in a message or as a reaction, waiting to be written.
+pub struct EmoteUsage {
+    pub message_id: MessageId,
+    pub guild_id: GuildId,
+    pub channel_id: GenericChannelId,
+    pub user_id: UserId,
+    pub emote_id: EmojiId,
+    /// `None` for reactions the client couldn't see the emote of.
+    pub emote_name: Option<String>,
+    pub usage_type: EmoteUsageType,
+    pub used_at: Timestamp,
+}
+
+/// A sticker sent in a message, waiting to be written.
+pub struct StickerUsage {
+    pub message_id: MessageId,
+    pub guild_id: GuildId,
+    pub channel_id: GenericChannelId,
+    pub user_id: UserId,
+    pub sticker_id: StickerId,
+    pub sticker_name: String,
+    pub used_at: Timestamp,
+}
+
+#[derive(Default)]
+pub(super) struct PendingExpressions {
+    emotes: Vec<EmoteUsage>,
+    stickers: Vec<StickerUsage>,
+}
+
+impl Database {
+    /// Queues an emote usage to be written on the next flush.
+    pub fn queue_emote_usage(&self, usage: EmoteUsage) {
+        self.pending_expressions.lock().unwrap().emotes.push(usage);
+    }
+
+    /// Queues a sticker usage to be written on the next flush.
+    pub fn queue_sticker_usage(&self, usage: StickerUsage) {
+        self.pending_expressions
+            .lock()
+            .unwrap()
+            .stickers
+            .push(usage);
+    }
+
+    /// Forgets a reaction, whether it is still queued or was already written.
+    pub async fn remove_reaction_usage(
+        &self,
+        message_id: MessageId,
+        user_id: UserId,
+        emote_id: EmojiId,
+    ) -> anyhow::Result<()> {
+        {
+            let mut pending = self.pending_expressions.lock().unwrap();
+            let previous_len = pending.emotes.len();
+            pending.emotes.retain(|usage| {
+                !(usage.usage_type == EmoteUsageType::Reaction
+                    && usage.message_id == message_id
+                    && usage.user_id == user_id
+                    && usage.emote_id == emote_id)
+            });
+
+            if pending.emotes.len() != previous_len {
+                return Ok(());
+            }
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM emote_usage
+            WHERE message_id = $1
+                AND user_id = $2
+                AND usage_type = 'reaction'
+                AND emote_id = (SELECT emote_id FROM emotes WHERE discord_id = $3)
+            "#,
+            message_id.get() as i64,
+            user_id.get() as i64,
+            emote_id.get() as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Writes everything queued so far in one transaction.
+    ///
+    /// The queue is taken up front, so a failed flush drops that batch rather than retrying it.
+    pub async fn flush_expression_usage(&self) -> anyhow::Result<()> {
+        let pending = std::mem::take(&mut *self.pending_expressions.lock().unwrap());
+        if pending.emotes.is_empty() && pending.stickers.is_empty() {
+            return Ok(());
+        }
+
+        let guild_ids: HashSet<i64> = pending
+            .emotes
+            .iter()
+            .map(|u| u.guild_id)
+            .chain(pending.stickers.iter().map(|u| u.guild_id))
+            .map(|id| id.get() as i64)
+            .collect();
+        let user_ids: HashSet<i64> = pending
+            .emotes
+            .iter()
+            .map(|u| u.user_id)
+            .chain(pending.stickers.iter().map(|u| u.user_id))
+            .map(|id| id.get() as i64)
+            .collect();
+
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO guilds (guild_id)
+            SELECT * FROM UNNEST($1::BIGINT[])
+            ON CONFLICT (guild_id) DO NOTHING
+            "#,
+            &guild_ids.into_iter().collect::<Vec<_>>(),
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO users (user_id)
+            SELECT * FROM UNNEST($1::BIGINT[])
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+            &user_ids.into_iter().collect::<Vec<_>>(),
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        if !pending.emotes.is_empty() {
+            write_emote_usage(&mut transaction, &pending.emotes).await?;
+        }
+        if !pending.stickers.is_empty() {
+            write_sticker_usage(&mut transaction, &pending.stickers).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+async fn write_emote_usage(
+    conn: &mut sqlx::PgConnection,
+    usages: &[EmoteUsage],
+) -> anyhow::Result<()> {
+    // an upsert can't touch the same row twice in one statement, so dedupe first, keeping a
+    // name if any usage has one.
+    let mut emotes: HashMap<i64, Option<&str>> = HashMap::new();
+    for usage in usages {
+        let name = emotes.entry(usage.emote_id.get() as i64).or_default();
+        if usage.emote_name.is_some() {
+            *name = usage.emote_name.as_deref();
+        }
+    }
+    // missing names are sent as empty strings, they never overwrite a known name.
+    let (discord_ids, names): (Vec<i64>, Vec<String>) = emotes
+        .into_iter()
+        .map(|(id, name)| (id, name.unwrap_or_default().to_string()))
+        .unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO emotes (discord_id, emote_name)
+        SELECT discord_id, COALESCE(NULLIF(emote_name, ''), 'unknown')
+        FROM UNNEST($1::BIGINT[], $2::TEXT[]) AS u(discord_id, emote_name)
+        ON CONFLICT (discord_id) DO UPDATE SET emote_name = EXCLUDED.emote_name
+            WHERE EXCLUDED.emote_name <> 'unknown'
+        "#,
+        &discord_ids,
+        &names,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO emote_usage
+            (message_id, guild_id, channel_id, emote_id, user_id, used_at, usage_type)
+        SELECT
+            u.message_id,
+            u.guild_id,
+            u.channel_id,
+            e.emote_id,
+            u.user_id,
+            u.used_at,
+            CASE WHEN u.is_reaction
+                THEN 'reaction'::EmoteUsageType
+                ELSE 'message'::EmoteUsageType
+            END
+        FROM UNNEST(
+            $1::BIGINT[], $2::BIGINT[], $3::BIGINT[], $4::BIGINT[], $5::BIGINT[],
+            $6::TIMESTAMPTZ[], $7::BOOLEAN[]
+        ) AS u(message_id, guild_id, channel_id, discord_id, user_id, used_at, is_reaction)
+        JOIN emotes e ON e.discord_id = u.discord_id
+        WHERE NOT EXISTS (
+            SELECT 1 FROM blocked_checked_emotes b
+            WHERE b.guild_id = u.guild_id AND b.emote_id = e.emote_id
+        )
+        ON CONFLICT DO NOTHING
+        "#,
+        &usages
+            .iter()
+            .map(|u| u.message_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.guild_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.channel_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.emote_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.user_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| to_offset_date_time(u.used_at))
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.usage_type == EmoteUsageType::Reaction)
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn write_sticker_usage(
+    conn: &mut sqlx::PgConnection,
+    usages: &[StickerUsage],
+) -> anyhow::Result<()> {
+    let stickers: HashMap<i64, &str> = usages
+        .iter()
+        .map(|u| (u.sticker_id.get() as i64, u.sticker_name.as_str()))
+        .collect();
+    let (sticker_ids, names): (Vec<i64>, Vec<String>) = stickers
+        .into_iter()
+        .map(|(id, name)| (id, name.to_string()))
+        .unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO stickers (sticker_id, sticker_name)
+        SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[])
+        ON CONFLICT (sticker_id) DO UPDATE SET sticker_name = EXCLUDED.sticker_name
+        "#,
+        &sticker_ids,
+        &names,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO sticker_usage (message_id, sticker_id, guild_id, user_id, channel_id, used_at)
+        SELECT u.message_id, u.sticker_id, u.guild_id, u.user_id, u.channel_id, u.used_at
+        FROM UNNEST(
+            $1::BIGINT[], $2::BIGINT[], $3::BIGINT[], $4::BIGINT[], $5::BIGINT[],
+            $6::TIMESTAMPTZ[]
+        ) AS u(message_id, sticker_id, guild_id, user_id, channel_id, used_at)
+        WHERE NOT EXISTS (
+            SELECT 1 FROM blocked_checked_stickers b
+            WHERE b.guild_id = u.guild_id AND b.sticker_id = u.sticker_id
+        )
+        ON CONFLICT DO NOTHING
+        "#,
+        &usages
+            .iter()
+            .map(|u| u.message_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.sticker_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.guild_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.user_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| u.channel_id.get() as i64)
+            .collect::<Vec<_>>(),
+        &usages
+            .iter()
+            .map(|u| to_offset_date_time(u.used_at))
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
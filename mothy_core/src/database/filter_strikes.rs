@@ -0,0 +1,58 @@
+use serenity::all::{GuildId, UserId};
+
+use super::Database;
+
+impl Database {
+    /// Records a filtered message, returning how many the user has had in the current window.
+    ///
+    /// The window starts at the first strike, strikes after it ends start a new one.
+    pub async fn add_filter_strike(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        window_minutes: u32,
+    ) -> anyhow::Result<u32> {
+        let strikes = sqlx::query_scalar!(
+            r#"
+            INSERT INTO filter_strikes (guild_id, user_id, strikes, window_started_at)
+            VALUES ($1, $2, 1, NOW())
+            ON CONFLICT (guild_id, user_id) DO UPDATE SET
+                strikes = CASE
+                    WHEN filter_strikes.window_started_at > NOW() - make_interval(mins => $3)
+                    THEN filter_strikes.strikes + 1
+                    ELSE 1
+                END,
+                window_started_at = CASE
+                    WHEN filter_strikes.window_started_at > NOW() - make_interval(mins => $3)
+                    THEN filter_strikes.window_started_at
+                    ELSE NOW()
+                END
+            RETURNING strikes
+            "#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            window_minutes as i32,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(strikes as u32)
+    }
+
+    /// Forgets a user's strikes, returning whether they had any.
+    pub async fn clear_filter_strikes(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM filter_strikes WHERE guild_id = $1 AND user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() != 0)
+    }
+}
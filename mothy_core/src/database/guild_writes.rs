@@ -0,0 +1,669 @@
+use arrayvec::ArrayString;
+use serenity::all::{GenericChannelId, GuildId, RoleId, RuleId, UserId};
+
+use super::GuildHandler;
+use crate::database_models::{
+    AutoresponseSettings, ColourMode, CotdRoleSettings, DmActivitySettings, FilterEscalation,
+    FilterSettings, GuildFeatures, IconPairingMode, LogSettings, ModRole, RegexTrigger,
+    StickyRoleMode, StickyRoleSettings, colours_to_json,
+};
+
+// Writes to child tables run in a transaction that first makes sure the parent `guilds` row exists.
+// Every write then refreshes the cached settings so readers never see a stale `GuildSettings`.
+impl GuildHandler {
+    async fn after_write(&self, guild_id: GuildId) {
+        // a failed reload already evicted the cached value, the next `get` will retry it.
+        let _ = self.refresh(guild_id).await;
+    }
+
+    pub async fn set_prefix(
+        &self,
+        guild_id: GuildId,
+        prefix: Option<ArrayString<6>>,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO guilds (guild_id, prefix) VALUES ($1, $2)
+            ON CONFLICT (guild_id) DO UPDATE SET prefix = EXCLUDED.prefix
+            "#,
+            guild_id.get() as i64,
+            prefix.as_ref().map(ArrayString::as_str),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_stock_prefix_disabled(
+        &self,
+        guild_id: GuildId,
+        stock_prefix_disabled: bool,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO guilds (guild_id, stock_prefix_disabled) VALUES ($1, $2)
+            ON CONFLICT (guild_id) DO UPDATE SET stock_prefix_disabled = EXCLUDED.stock_prefix_disabled
+            "#,
+            guild_id.get() as i64,
+            stock_prefix_disabled,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    /// Clears the custom prefix and re-enables the stock one, so prefix commands stay reachable.
+    pub async fn reset_prefix(&self, guild_id: GuildId) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO guilds (guild_id) VALUES ($1)
+            ON CONFLICT (guild_id) DO UPDATE SET prefix = NULL, stock_prefix_disabled = FALSE
+            "#,
+            guild_id.get() as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_features(
+        &self,
+        guild_id: GuildId,
+        features: GuildFeatures,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO guilds (guild_id, feature_flags) VALUES ($1, $2)
+            ON CONFLICT (guild_id) DO UPDATE SET feature_flags = EXCLUDED.feature_flags
+            "#,
+            guild_id.get() as i64,
+            i16::from(features.bits()),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    /// Bans or unbans a guild from the bot, the reason is cleared on unban.
+    ///
+    /// Either way the guild no longer counts as having rejoined.
+    pub async fn set_banned(
+        &self,
+        guild_id: GuildId,
+        banned: bool,
+        reason: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO guilds (guild_id, banned, ban_reason) VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id) DO UPDATE
+            SET banned = EXCLUDED.banned, ban_reason = EXCLUDED.ban_reason, rejoined = FALSE
+            "#,
+            guild_id.get() as i64,
+            banned,
+            reason.filter(|_| banned),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    /// Marks a banned guild as having invited the bot back.
+    pub async fn set_rejoined(&self, guild_id: GuildId) -> anyhow::Result<()> {
+        sqlx::query!(
+            "UPDATE guilds SET rejoined = TRUE WHERE guild_id = $1",
+            guild_id.get() as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_log_settings(
+        &self,
+        guild_id: GuildId,
+        settings: LogSettings,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO log_settings (
+                guild_id, join_logs_channel_id, blacklist_logs_channel_id, voice_logs_channel_id
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id) DO UPDATE SET
+                join_logs_channel_id = EXCLUDED.join_logs_channel_id,
+                blacklist_logs_channel_id = EXCLUDED.blacklist_logs_channel_id,
+                voice_logs_channel_id = EXCLUDED.voice_logs_channel_id
+            "#,
+            guild_id.get() as i64,
+            settings.join_logs_channel_id.map(|id| id.get() as i64),
+            settings.blacklist_logs_channel_id.map(|id| id.get() as i64),
+            settings.voice_logs_channel_id.map(|id| id.get() as i64),
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_filter_settings(
+        &self,
+        guild_id: GuildId,
+        settings: &FilterSettings,
+    ) -> anyhow::Result<()> {
+        let bypass_roles = role_ids_to_raw(&settings.bypass_roles);
+
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO filter_settings (
+                guild_id,
+                is_enabled,
+                bypass_roles,
+                timeout_minutes,
+                escalation,
+                escalation_strikes,
+                strike_window_minutes
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (guild_id) DO UPDATE SET
+                is_enabled = EXCLUDED.is_enabled,
+                bypass_roles = EXCLUDED.bypass_roles,
+                timeout_minutes = EXCLUDED.timeout_minutes,
+                escalation = EXCLUDED.escalation,
+                escalation_strikes = EXCLUDED.escalation_strikes,
+                strike_window_minutes = EXCLUDED.strike_window_minutes
+            "#,
+            guild_id.get() as i64,
+            settings.is_enabled,
+            &bypass_roles,
+            settings.timeout_minutes as i32,
+            settings.escalation as FilterEscalation,
+            settings.escalation_strikes as i32,
+            settings.strike_window_minutes as i32,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_autoresponse_settings(
+        &self,
+        guild_id: GuildId,
+        settings: &AutoresponseSettings,
+    ) -> anyhow::Result<()> {
+        let allowed_roles = role_ids_to_raw(&settings.allowed_roles);
+        let allowed_permissions: Vec<i64> = settings
+            .allowed_permissions
+            .iter()
+            .map(|permissions| permissions.bits() as i64)
+            .collect();
+
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO autoresponse_settings (
+                guild_id, allowed_roles, allowed_permissions, logging_channel_id, log_changes
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (guild_id) DO UPDATE SET
+                allowed_roles = EXCLUDED.allowed_roles,
+                allowed_permissions = EXCLUDED.allowed_permissions,
+                logging_channel_id = EXCLUDED.logging_channel_id,
+                log_changes = EXCLUDED.log_changes
+            "#,
+            guild_id.get() as i64,
+            &allowed_roles,
+            &allowed_permissions,
+            settings.logging_channel_id.map(|id| id.get() as i64),
+            settings.log_changes,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_dm_activity_settings(
+        &self,
+        guild_id: GuildId,
+        settings: DmActivitySettings,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO dm_activity_settings (
+                guild_id, cooldown_seconds, announce_channel_id, retention_days
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id) DO UPDATE SET
+                cooldown_seconds = EXCLUDED.cooldown_seconds,
+                announce_channel_id = EXCLUDED.announce_channel_id,
+                retention_days = EXCLUDED.retention_days
+            "#,
+            guild_id.get() as i64,
+            settings.cooldown_seconds as i32,
+            settings.announce_channel_id.map(|id| id.get() as i64),
+            settings.retention_days.map(i16::from),
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn delete_dm_activity_settings(&self, guild_id: GuildId) -> anyhow::Result<()> {
+        sqlx::query!(
+            "DELETE FROM dm_activity_settings WHERE guild_id = $1",
+            guild_id.get() as i64
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_sticky_role_settings(
+        &self,
+        guild_id: GuildId,
+        settings: &StickyRoleSettings,
+    ) -> anyhow::Result<()> {
+        let allowlist_roles = role_ids_to_raw(&settings.allowlist_roles);
+        let denylist_roles = role_ids_to_raw(&settings.denylist_roles);
+
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sticky_roles_settings (
+                guild_id, allowlist_roles, denylist_roles, mode, is_enabled
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (guild_id) DO UPDATE SET
+                allowlist_roles = EXCLUDED.allowlist_roles,
+                denylist_roles = EXCLUDED.denylist_roles,
+                mode = EXCLUDED.mode,
+                is_enabled = EXCLUDED.is_enabled
+            "#,
+            guild_id.get() as i64,
+            &allowlist_roles,
+            &denylist_roles,
+            settings.mode as StickyRoleMode,
+            settings.is_enabled,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn delete_sticky_role_settings(&self, guild_id: GuildId) -> anyhow::Result<()> {
+        sqlx::query!(
+            "DELETE FROM sticky_roles_settings WHERE guild_id = $1",
+            guild_id.get() as i64
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn set_cotd_role_settings(
+        &self,
+        guild_id: GuildId,
+        settings: &CotdRoleSettings,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO cotd_role_settings (
+                guild_id, role_id, is_enabled, suffix_enabled, colour_mode, icon_pairing_mode,
+                colours, icons, svg_target_colour, rotation_time
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (guild_id, role_id) DO UPDATE SET
+                is_enabled = EXCLUDED.is_enabled,
+                suffix_enabled = EXCLUDED.suffix_enabled,
+                colour_mode = EXCLUDED.colour_mode,
+                icon_pairing_mode = EXCLUDED.icon_pairing_mode,
+                colours = EXCLUDED.colours,
+                icons = EXCLUDED.icons,
+                svg_target_colour = EXCLUDED.svg_target_colour,
+                rotation_time = EXCLUDED.rotation_time
+            "#,
+            guild_id.get() as i64,
+            settings.role_id.get() as i64,
+            settings.is_enabled(),
+            settings.suffix_enabled(),
+            settings.colour_mode as ColourMode,
+            settings.icon_pairing_mode as IconPairingMode,
+            colours_to_json(&settings.colours),
+            &settings.icons,
+            settings.svg_target_colour.map(|c| c as i32),
+            settings.rotation_time,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn delete_cotd_role_settings(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM cotd_role_settings WHERE guild_id = $1 AND role_id = $2",
+            guild_id.get() as i64,
+            role_id.get() as i64
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(result.rows_affected() != 0)
+    }
+
+    pub async fn set_mod_role(&self, guild_id: GuildId, mod_role: ModRole) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO mod_roles (guild_id, role_id, permissions)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (role_id) DO UPDATE SET permissions = EXCLUDED.permissions
+            "#,
+            guild_id.get() as i64,
+            mod_role.role_id.get() as i64,
+            mod_role.permissions.bits() as i16,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn delete_mod_role(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM mod_roles WHERE guild_id = $1 AND role_id = $2",
+            guild_id.get() as i64,
+            role_id.get() as i64
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(result.rows_affected() != 0)
+    }
+
+    /// Allows or denies a role bypassing an automod rule, making it a mod role if it isn't one.
+    pub async fn set_automod_rule_override(
+        &self,
+        guild_id: GuildId,
+        rule_id: RuleId,
+        role_id: RoleId,
+        is_allowed: bool,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO mod_roles (guild_id, role_id) VALUES ($1, $2)
+            ON CONFLICT (role_id) DO NOTHING
+            "#,
+            guild_id.get() as i64,
+            role_id.get() as i64,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO automod_rule_overrides (rule_id, role_id, is_allowed)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (rule_id, role_id) DO UPDATE SET is_allowed = EXCLUDED.is_allowed
+            "#,
+            rule_id.get().to_string(),
+            role_id.get() as i64,
+            is_allowed,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn delete_automod_rule_override(
+        &self,
+        guild_id: GuildId,
+        rule_id: RuleId,
+        role_id: RoleId,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM automod_rule_overrides o
+            USING mod_roles m
+            WHERE m.role_id = o.role_id AND m.guild_id = $1 AND o.rule_id = $2 AND o.role_id = $3
+            "#,
+            guild_id.get() as i64,
+            rule_id.get().to_string(),
+            role_id.get() as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(result.rows_affected() != 0)
+    }
+
+    /// Inserts a new trigger, the `id` of the passed trigger is ignored and the new one returned.
+    pub async fn insert_regex_trigger(
+        &self,
+        guild_id: GuildId,
+        trigger: &RegexTrigger,
+    ) -> anyhow::Result<u64> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO regex_triggers (
+                guild_id, channel_id, pattern, trigger_context, trigger_metadata,
+                is_recursive, is_fancy, is_enabled
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+            guild_id.get() as i64,
+            trigger.channel_id.map(|id| id.get() as i64),
+            trigger.pattern.as_str(),
+            i16::from(trigger.trigger_context.bits()),
+            serde_json::to_value(&trigger.trigger_metadata)?,
+            trigger.is_recursive(),
+            trigger.pattern.is_fancy(),
+            trigger.is_enabled(),
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(record.id as u64)
+    }
+
+    /// Overwrites the stored trigger with the same `id`, returns `false` if it doesn't exist.
+    pub async fn update_regex_trigger(
+        &self,
+        guild_id: GuildId,
+        trigger: &RegexTrigger,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE regex_triggers SET
+                channel_id = $3,
+                pattern = $4,
+                trigger_context = $5,
+                trigger_metadata = $6,
+                is_recursive = $7,
+                is_fancy = $8,
+                is_enabled = $9
+            WHERE guild_id = $1 AND id = $2
+            "#,
+            guild_id.get() as i64,
+            trigger.id as i64,
+            trigger.channel_id.map(|id| id.get() as i64),
+            trigger.pattern.as_str(),
+            i16::from(trigger.trigger_context.bits()),
+            serde_json::to_value(&trigger.trigger_metadata)?,
+            trigger.is_recursive(),
+            trigger.pattern.is_fancy(),
+            trigger.is_enabled(),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(result.rows_affected() != 0)
+    }
+
+    pub async fn delete_regex_trigger(&self, guild_id: GuildId, id: u64) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM regex_triggers WHERE guild_id = $1 AND id = $2",
+            guild_id.get() as i64,
+            id as i64
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(result.rows_affected() != 0)
+    }
+
+    pub async fn set_regex_denylist_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: GenericChannelId,
+        is_recursive: bool,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO regex_global_denylist_channels (guild_id, channel_id, is_recursive)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id, channel_id) DO UPDATE SET is_recursive = EXCLUDED.is_recursive
+            "#,
+            guild_id.get() as i64,
+            channel_id.get() as i64,
+            is_recursive,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        self.after_write(guild_id).await;
+        Ok(())
+    }
+
+    pub async fn delete_regex_denylist_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: GenericChannelId,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM regex_global_denylist_channels WHERE guild_id = $1 AND channel_id = $2",
+            guild_id.get() as i64,
+            channel_id.get() as i64
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.after_write(guild_id).await;
+        Ok(result.rows_affected() != 0)
+    }
+}
+
+/// Makes sure the parent `guilds` row exists so settings tables can reference it.
+pub(super) async fn insert_guild(
+    conn: &mut sqlx::PgConnection,
+    guild_id: GuildId,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        "INSERT INTO guilds (guild_id) VALUES ($1) ON CONFLICT (guild_id) DO NOTHING",
+        guild_id.get() as i64
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Makes sure the `users` row exists for tables that reference it.
+pub(super) async fn insert_user(
+    conn: &mut sqlx::PgConnection,
+    user_id: UserId,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        "INSERT INTO users (user_id) VALUES ($1) ON CONFLICT (user_id) DO NOTHING",
+        user_id.get() as i64
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub(super) fn role_ids_to_raw(role_ids: &[RoleId]) -> Vec<i64> {
+    role_ids.iter().map(|id| id.get() as i64).collect()
+}
@@ -0,0 +1,124 @@
+use serenity::all::{GuildId, UserId};
+
+use super::Database;
+use super::guild_writes::insert_guild;
+
+/// A link blacklist entry, global if it has no guild.
+pub struct LinkBlacklistEntry {
+    pub id: i64,
+    pub guild_id: Option<GuildId>,
+    pub pattern: String,
+    pub is_exception: bool,
+}
+
+struct RawLinkBlacklistEntry {
+    id: i64,
+    guild_id: Option<i64>,
+    pattern: String,
+    is_exception: bool,
+}
+
+impl From<RawLinkBlacklistEntry> for LinkBlacklistEntry {
+    fn from(raw: RawLinkBlacklistEntry) -> Self {
+        LinkBlacklistEntry {
+            id: raw.id,
+            guild_id: raw.guild_id.map(|id| GuildId::new(id as u64)),
+            pattern: raw.pattern,
+            is_exception: raw.is_exception,
+        }
+    }
+}
+
+impl Database {
+    /// Every entry, global and for all guilds.
+    pub async fn get_link_blacklist(&self) -> anyhow::Result<Vec<LinkBlacklistEntry>> {
+        let raws = sqlx::query_as!(
+            RawLinkBlacklistEntry,
+            "SELECT id, guild_id, pattern, is_exception FROM link_blacklist"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(raws.into_iter().map(LinkBlacklistEntry::from).collect())
+    }
+
+    /// Adds entries, skipping ones that already exist. Returns how many were added.
+    pub async fn insert_link_blacklist_entries(
+        &self,
+        guild_id: Option<GuildId>,
+        patterns: &[String],
+        is_exception: bool,
+        added_by: UserId,
+    ) -> anyhow::Result<u64> {
+        let mut transaction = self.pool.begin().await?;
+        if let Some(guild_id) = guild_id {
+            insert_guild(&mut transaction, guild_id).await?;
+        }
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO link_blacklist (guild_id, pattern, is_exception, added_by)
+            SELECT $1, pattern, $3, $4 FROM UNNEST($2::TEXT[]) AS pattern
+            ON CONFLICT DO NOTHING
+            "#,
+            guild_id.map(|id| id.get() as i64),
+            patterns,
+            is_exception,
+            added_by.get() as i64,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Removes an entry or exception, returning whether there was one.
+    pub async fn delete_link_blacklist_entry(
+        &self,
+        guild_id: Option<GuildId>,
+        pattern: &str,
+        is_exception: bool,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM link_blacklist
+            WHERE guild_id IS NOT DISTINCT FROM $1 AND pattern = $2 AND is_exception = $3
+            "#,
+            guild_id.map(|id| id.get() as i64),
+            pattern,
+            is_exception,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() != 0)
+    }
+
+    /// Global entries and the guild's own whose pattern contains `query`.
+    pub async fn search_link_blacklist(
+        &self,
+        guild_id: GuildId,
+        query: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<LinkBlacklistEntry>> {
+        let raws = sqlx::query_as!(
+            RawLinkBlacklistEntry,
+            r#"
+            SELECT id, guild_id, pattern, is_exception
+            FROM link_blacklist
+            WHERE (guild_id IS NULL OR guild_id = $1)
+                AND STRPOS(LOWER(pattern), LOWER($2)) > 0
+            ORDER BY guild_id NULLS FIRST, pattern
+            LIMIT $3
+            "#,
+            guild_id.get() as i64,
+            query,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(raws.into_iter().map(LinkBlacklistEntry::from).collect())
+    }
+}
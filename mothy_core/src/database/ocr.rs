@@ -0,0 +1,34 @@
+use serenity::all::{GenericChannelId, GuildId};
+
+use super::Database;
+use super::guild_writes::insert_guild;
+use crate::ocr::TextStats;
+
+impl Database {
+    /// Records the size of the text recognised in one image.
+    pub async fn insert_ocr_analytics(
+        &self,
+        guild_id: GuildId,
+        channel_id: GenericChannelId,
+        stats: &TextStats,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO ocr_analytics (guild_id, channel_id, word_count, line_count)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            guild_id.get() as i64,
+            channel_id.get() as i64,
+            i16::try_from(stats.word_count).unwrap_or(i16::MAX),
+            i16::try_from(stats.line_count).unwrap_or(i16::MAX),
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use serenity::all::{GuildId, RoleId, UserId};
+
+use super::Database;
+use super::guild_writes::{insert_guild, insert_user, role_ids_to_raw};
+
+impl Database {
+    /// Stores the roles a member had when they left, replacing any older snapshot.
+    pub async fn save_sticky_roles(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        roles: &[RoleId],
+    ) -> anyhow::Result<()> {
+        let roles = role_ids_to_raw(roles);
+
+        let mut transaction = self.pool.begin().await?;
+        insert_guild(&mut transaction, guild_id).await?;
+        insert_user(&mut transaction, user_id).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sticky_roles (user_id, guild_id, roles, last_updated)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (user_id, guild_id) DO UPDATE SET
+                roles = EXCLUDED.roles,
+                last_updated = EXCLUDED.last_updated
+            "#,
+            user_id.get() as i64,
+            guild_id.get() as i64,
+            &roles,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// The roles saved for a member, empty if there are none.
+    pub async fn get_sticky_roles(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> anyhow::Result<Vec<RoleId>> {
+        let record = sqlx::query!(
+            "SELECT roles FROM sticky_roles WHERE user_id = $1 AND guild_id = $2",
+            user_id.get() as i64,
+            guild_id.get() as i64,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record
+            .map(|r| r.roles)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| RoleId::new(id as u64))
+            .collect())
+    }
+
+    /// Forgets the roles saved for a member.
+    pub async fn delete_sticky_roles(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            "DELETE FROM sticky_roles WHERE user_id = $1 AND guild_id = $2",
+            user_id.get() as i64,
+            guild_id.get() as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use serenity::all::UserId;
+
+use super::Database;
+
+impl Database {
+    /// Whether the user is banned from using the bot, cached after the first lookup.
+    pub async fn is_bot_banned(&self, user_id: UserId) -> anyhow::Result<bool> {
+        if let Some(banned) = self.bot_banned_users.get(&user_id) {
+            return Ok(*banned);
+        }
+
+        let banned = sqlx::query_scalar!(
+            "SELECT is_bot_banned FROM users WHERE user_id = $1",
+            user_id.get() as i64
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .unwrap_or(false);
+
+        self.bot_banned_users.insert(user_id, banned);
+        Ok(banned)
+    }
+
+    /// Bans or unbans a user from the bot, the reason is cleared on unban.
+    pub async fn set_bot_banned(
+        &self,
+        user_id: UserId,
+        banned: bool,
+        reason: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO users (user_id, is_bot_banned, bot_ban_reason) VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE
+            SET is_bot_banned = EXCLUDED.is_bot_banned, bot_ban_reason = EXCLUDED.bot_ban_reason
+            "#,
+            user_id.get() as i64,
+            banned,
+            reason.filter(|_| banned),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.bot_banned_users.insert(user_id, banned);
+        Ok(())
+    }
+}
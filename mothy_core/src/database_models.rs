@@ -1,7 +1,7 @@
 use arrayvec::ArrayString;
 use serde::{Deserialize, Serialize};
-use serenity::all::{Colour, GenericChannelId, RoleColours, RoleId, RuleId};
-use sqlx::types::time::Time;
+use serenity::all::{Colour, GenericChannelId, Permissions, RoleColours, RoleId, RuleId, UserId};
+use sqlx::types::time::{OffsetDateTime, Time};
 
 pub(super) fn truncate_convert<const MAX_SIZE: usize>(mut s: String) -> ArrayString<MAX_SIZE> {
     if s.len() > MAX_SIZE {
@@ -16,15 +16,21 @@ pub(super) fn truncate_convert<const MAX_SIZE: usize>(mut s: String) -> ArrayStr
 pub struct GuildSettings {
     pub banned: bool,
     pub rejoined: bool,
+    pub stock_prefix_disabled: bool,
     pub prefix: Option<ArrayString<6>>,
     pub features: GuildFeatures,
     pub regex_triggers: Vec<RegexTrigger>,
+    /// Stored triggers that failed to load, these are disabled and never evaluated.
+    pub invalid_regex_triggers: Vec<InvalidRegexTrigger>,
     pub regex_denylist: Vec<GlobalRegexDenylistChannel>,
     pub mod_roles: Vec<ModRole>,
     pub automod_rule_overrides: Vec<AutomodRuleOverrides>,
     pub sticky_role_settings: StickyRoleSettings,
     pub cotd_settings: Vec<CotdRoleSettings>,
     pub dm_activity_settings: DmActivitySettings,
+    pub log_settings: LogSettings,
+    pub filter_settings: FilterSettings,
+    pub autoresponse_settings: AutoresponseSettings,
 }
 
 bitflags::bitflags! {
@@ -75,6 +81,167 @@ impl From<RawDmActivitySettings> for DmActivitySettings {
     }
 }
 
+#[derive(Default, Clone, Copy)]
+pub struct LogSettings {
+    pub join_logs_channel_id: Option<GenericChannelId>,
+    pub blacklist_logs_channel_id: Option<GenericChannelId>,
+    pub voice_logs_channel_id: Option<GenericChannelId>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct RawLogSettings {
+    pub join_logs_channel_id: Option<i64>,
+    pub blacklist_logs_channel_id: Option<i64>,
+    pub voice_logs_channel_id: Option<i64>,
+}
+
+impl From<RawLogSettings> for LogSettings {
+    fn from(raw: RawLogSettings) -> Self {
+        LogSettings {
+            join_logs_channel_id: raw
+                .join_logs_channel_id
+                .map(|id| GenericChannelId::new(id as u64)),
+            blacklist_logs_channel_id: raw
+                .blacklist_logs_channel_id
+                .map(|id| GenericChannelId::new(id as u64)),
+            voice_logs_channel_id: raw
+                .voice_logs_channel_id
+                .map(|id| GenericChannelId::new(id as u64)),
+        }
+    }
+}
+
+/// Discord doesn't allow timeouts longer than 28 days.
+pub const MAX_FILTER_TIMEOUT_MINUTES: u32 = 28 * 24 * 60;
+
+#[derive(Clone)]
+pub struct FilterSettings {
+    pub bypass_roles: Vec<RoleId>,
+    pub is_enabled: bool,
+    /// How long someone is timed out for each filtered message, 0 to not time out.
+    pub timeout_minutes: u32,
+    /// Taken instead of the timeout once someone reaches `escalation_strikes`.
+    pub escalation: FilterEscalation,
+    pub escalation_strikes: u32,
+    /// Strikes are counted from the first one until this many minutes later.
+    pub strike_window_minutes: u32,
+}
+
+impl Default for FilterSettings {
+    /// Matches the column defaults, filtered messages are only deleted.
+    fn default() -> Self {
+        FilterSettings {
+            bypass_roles: vec![],
+            is_enabled: false,
+            timeout_minutes: 0,
+            escalation: FilterEscalation::None,
+            escalation_strikes: 3,
+            strike_window_minutes: 1440,
+        }
+    }
+}
+
+impl FilterSettings {
+    /// What is done to someone for their `strikes`th filtered message in the window.
+    #[must_use]
+    pub fn punishment(&self, strikes: u32) -> FilterPunishment {
+        match self.escalation {
+            FilterEscalation::Kick if strikes >= self.escalation_strikes => FilterPunishment::Kick,
+            FilterEscalation::Ban if strikes >= self.escalation_strikes => FilterPunishment::Ban,
+            _ if self.timeout_minutes != 0 => {
+                FilterPunishment::Timeout(self.timeout_minutes.min(MAX_FILTER_TIMEOUT_MINUTES))
+            }
+            _ => FilterPunishment::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "filterescalation")]
+#[sqlx(rename_all = "lowercase")]
+pub enum FilterEscalation {
+    None,
+    Kick,
+    Ban,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterPunishment {
+    None,
+    /// For this many minutes.
+    Timeout(u32),
+    Kick,
+    Ban,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct RawFilterSettings {
+    pub bypass_roles: Vec<i64>,
+    pub is_enabled: bool,
+    pub timeout_minutes: i32,
+    pub escalation: FilterEscalation,
+    pub escalation_strikes: i32,
+    pub strike_window_minutes: i32,
+}
+
+impl From<RawFilterSettings> for FilterSettings {
+    fn from(raw: RawFilterSettings) -> Self {
+        FilterSettings {
+            bypass_roles: raw
+                .bypass_roles
+                .into_iter()
+                .map(|id| RoleId::new(id as u64))
+                .collect(),
+            is_enabled: raw.is_enabled,
+            timeout_minutes: raw.timeout_minutes as u32,
+            escalation: raw.escalation,
+            escalation_strikes: raw.escalation_strikes as u32,
+            strike_window_minutes: raw.strike_window_minutes as u32,
+        }
+    }
+}
+
+/// Who besides server managers may manage regex triggers, and where changes are logged.
+#[derive(Default, Clone)]
+pub struct AutoresponseSettings {
+    pub allowed_roles: Vec<RoleId>,
+    /// Each entry is a set of permissions, holding every permission of any one entry is enough.
+    pub allowed_permissions: Vec<Permissions>,
+    pub logging_channel_id: Option<GenericChannelId>,
+    pub log_changes: bool,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct RawAutoresponseSettings {
+    pub allowed_roles: Option<Vec<i64>>,
+    pub allowed_permissions: Option<Vec<i64>>,
+    pub logging_channel_id: Option<i64>,
+    pub log_changes: bool,
+}
+
+impl From<RawAutoresponseSettings> for AutoresponseSettings {
+    fn from(raw: RawAutoresponseSettings) -> Self {
+        AutoresponseSettings {
+            allowed_roles: raw
+                .allowed_roles
+                .unwrap_or_default()
+                .into_iter()
+                .map(|id| RoleId::new(id as u64))
+                .collect(),
+            allowed_permissions: raw
+                .allowed_permissions
+                .unwrap_or_default()
+                .into_iter()
+                .map(|bits| Permissions::from_bits_truncate(bits as u64))
+                .collect(),
+            logging_channel_id: raw
+                .logging_channel_id
+                .map(|id| GenericChannelId::new(id as u64)),
+            log_changes: raw.log_changes,
+        }
+    }
+}
+
 #[bool_to_bitflags::bool_to_bitflags(owning_setters)]
 #[derive(Clone)]
 pub struct RegexTrigger {
@@ -99,18 +266,56 @@ pub struct RawRegexTrigger {
     pub is_fancy: bool,
 }
 
-impl From<RawRegexTrigger> for RegexTrigger {
-    fn from(raw: RawRegexTrigger) -> Self {
-        let pattern = if raw.is_fancy {
-            Pattern::Fancy(fancy_regex::Regex::new(&raw.pattern).expect("valid regex"))
-        } else {
-            Pattern::Simple(regex::Regex::new(&raw.pattern).expect("valid regex"))
+impl RegexTrigger {
+    /// A new enabled trigger, the id is assigned by the database on insert.
+    #[must_use]
+    pub fn new(
+        pattern: Pattern,
+        channel_id: Option<GenericChannelId>,
+        trigger_context: TriggerContext,
+        trigger_metadata: TriggerMetadata,
+        is_recursive: bool,
+    ) -> Self {
+        RegexTrigger {
+            id: 0,
+            channel_id,
+            pattern,
+            trigger_context,
+            trigger_metadata,
+            __generated_flags: RegexTriggerGeneratedFlags::empty(),
+        }
+        .set_is_enabled(true)
+        .set_is_recursive(is_recursive)
+    }
+}
+
+/// A stored trigger that could not be loaded, kept so it can be shown and removed.
+#[derive(Clone)]
+pub struct InvalidRegexTrigger {
+    pub id: u64,
+    pub pattern: String,
+    /// Whether the row was still enabled when it was found to be invalid.
+    pub was_enabled: bool,
+    pub reason: String,
+}
+
+impl TryFrom<RawRegexTrigger> for RegexTrigger {
+    type Error = InvalidRegexTrigger;
+
+    fn try_from(raw: RawRegexTrigger) -> Result<Self, Self::Error> {
+        let invalid = |reason: String| InvalidRegexTrigger {
+            id: raw.id as u64,
+            pattern: raw.pattern.clone(),
+            was_enabled: raw.is_enabled,
+            reason,
         };
 
-        let trigger_metadata: TriggerMetadata = serde_json::from_value(raw.trigger_metadata)
-            .expect("failed to deserialize TriggerMetadata");
+        let pattern = Pattern::new(&raw.pattern, raw.is_fancy)
+            .map_err(|e| invalid(format!("invalid pattern: {e}")))?;
+        let trigger_metadata = TriggerMetadata::deserialize(&raw.trigger_metadata)
+            .map_err(|e| invalid(format!("invalid metadata: {e}")))?;
 
-        RegexTrigger {
+        Ok(RegexTrigger {
             id: raw.id as u64,
             channel_id: raw.channel_id.map(|id| GenericChannelId::new(id as u64)),
             pattern,
@@ -119,7 +324,7 @@ impl From<RawRegexTrigger> for RegexTrigger {
             __generated_flags: RegexTriggerGeneratedFlags::empty(),
         }
         .set_is_enabled(raw.is_enabled)
-        .set_is_recursive(raw.is_recursive)
+        .set_is_recursive(raw.is_recursive))
     }
 }
 
@@ -183,22 +388,59 @@ pub struct ModRole {
 #[derive(sqlx::FromRow)]
 pub struct RawModRole {
     pub role_id: i64,
-    pub permissions: i64,
+    pub permissions: i16,
 }
 
 impl From<RawModRole> for ModRole {
     fn from(raw: RawModRole) -> Self {
         ModRole {
             role_id: RoleId::new(raw.role_id as u64),
-            permissions: ModRolePermissions::from_bits_truncate(raw.permissions as u8),
+            permissions: ModRolePermissions::from_bits_truncate(raw.permissions as u16),
         }
     }
 }
 
 bitflags::bitflags! {
-    #[derive(Clone, Copy, Default, Hash)]
-    pub struct ModRolePermissions: u8 {
-        const EXIST = 1;
+    /// What a mod role lets its members do in the bot, on top of their Discord permissions.
+    // bit 0 was a placeholder and is ignored.
+    #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+    pub struct ModRolePermissions: u16 {
+        const MANAGE_TRIGGERS = 1 << 1;
+        const MANAGE_STICKY_ROLES = 1 << 2;
+        const MANAGE_COTD = 1 << 3;
+        const MANAGE_FILTERS = 1 << 4;
+        const BYPASS_FILTERS = 1 << 5;
+        const VIEW_LOGS = 1 << 6;
+    }
+}
+
+impl ModRolePermissions {
+    /// The Discord permission that grants each flag to members without a mod role.
+    const DISCORD_EQUIVALENTS: [(ModRolePermissions, Permissions); 6] = [
+        (Self::MANAGE_TRIGGERS, Permissions::MANAGE_GUILD),
+        (Self::MANAGE_STICKY_ROLES, Permissions::MANAGE_ROLES),
+        (Self::MANAGE_COTD, Permissions::MANAGE_ROLES),
+        (Self::MANAGE_FILTERS, Permissions::MANAGE_GUILD),
+        (Self::BYPASS_FILTERS, Permissions::MODERATE_MEMBERS),
+        (Self::VIEW_LOGS, Permissions::VIEW_AUDIT_LOG),
+    ];
+
+    /// Everything a member may do, from the mod roles they have and their Discord permissions.
+    #[must_use]
+    pub fn resolve(mod_roles: &[ModRole], roles: &[RoleId], permissions: Permissions) -> Self {
+        if permissions.administrator() {
+            return Self::all();
+        }
+
+        let from_discord = Self::DISCORD_EQUIVALENTS
+            .iter()
+            .filter(|(_, equivalent)| permissions.contains(*equivalent))
+            .fold(Self::empty(), |acc, (flag, _)| acc | *flag);
+
+        mod_roles
+            .iter()
+            .filter(|mod_role| roles.contains(&mod_role.role_id))
+            .fold(from_discord, |acc, mod_role| acc | mod_role.permissions)
     }
 }
 
@@ -208,19 +450,137 @@ pub enum Pattern {
     Fancy(fancy_regex::Regex),
 }
 
+/// The most memory a compiled pattern may use, well below `regex`'s default of 10MiB.
+pub(crate) const PATTERN_SIZE_LIMIT: usize = 1 << 18;
+/// How many steps a fancy pattern may backtrack before matching gives up.
+const PATTERN_BACKTRACK_LIMIT: usize = 100_000;
+
+impl Pattern {
+    /// Compiles `pattern`, as a fancy regex if `fancy` is set.
+    ///
+    /// Patterns come from guild moderators and run on every message, so they are compiled with
+    /// limits on their size and, for fancy patterns, on backtracking.
+    pub fn new(pattern: &str, fancy: bool) -> anyhow::Result<Self> {
+        Ok(if fancy {
+            Pattern::Fancy(
+                fancy_regex::RegexBuilder::new(pattern)
+                    .backtrack_limit(PATTERN_BACKTRACK_LIMIT)
+                    .delegate_size_limit(PATTERN_SIZE_LIMIT)
+                    .delegate_dfa_size_limit(PATTERN_SIZE_LIMIT)
+                    .build()?,
+            )
+        } else {
+            Pattern::Simple(
+                regex::RegexBuilder::new(pattern)
+                    .size_limit(PATTERN_SIZE_LIMIT)
+                    .dfa_size_limit(PATTERN_SIZE_LIMIT)
+                    .build()?,
+            )
+        })
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Pattern::Simple(regex) => regex.as_str(),
+            Pattern::Fancy(regex) => regex.as_str(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_fancy(&self) -> bool {
+        matches!(self, Pattern::Fancy(_))
+    }
+
+    /// Whether the pattern matches, a fancy regex that errors while matching counts as no match.
+    #[must_use]
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Pattern::Simple(regex) => regex.is_match(text),
+            Pattern::Fancy(regex) => regex.is_match(text).unwrap_or(false),
+        }
+    }
+
+    /// The groups of the first match, `None` if nothing matched.
+    ///
+    /// Unlike [`Self::is_match`], a fancy regex that errors while matching is reported.
+    pub fn captures<'t>(&self, text: &'t str) -> anyhow::Result<Option<Vec<Option<&'t str>>>> {
+        Ok(match self {
+            Pattern::Simple(regex) => regex
+                .captures(text)
+                .map(|c| c.iter().map(|m| m.map(|m| m.as_str())).collect()),
+            Pattern::Fancy(regex) => regex
+                .captures(text)?
+                .map(|c| c.iter().map(|m| m.map(|m| m.as_str())).collect()),
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct AutomodRuleOverrides {
     pub rule_id: RuleId,
     pub roles: Vec<(RoleId, bool)>,
 }
 
+impl AutomodRuleOverrides {
+    /// Whether a member with `roles` is exempt from the rule, a denying role wins over an allowing
+    /// one.
+    #[must_use]
+    pub fn exempts(&self, roles: &[RoleId]) -> bool {
+        let mut allowed = false;
+        for (role_id, is_allowed) in &self.roles {
+            if roles.contains(role_id) {
+                if !is_allowed {
+                    return false;
+                }
+                allowed = true;
+            }
+        }
+
+        allowed
+    }
+
+    /// Groups rows into one entry per rule, skipping rule ids that aren't snowflakes.
+    #[must_use]
+    pub fn from_raw(raws: Vec<RawAutomodRuleOverride>) -> Vec<Self> {
+        let mut overrides: Vec<Self> = Vec::new();
+        for raw in raws {
+            let Ok(rule_id) = raw.rule_id.parse::<u64>() else {
+                continue;
+            };
+            let rule_id = RuleId::new(rule_id);
+            let role = (RoleId::new(raw.role_id as u64), raw.is_allowed);
+
+            match overrides.iter_mut().find(|o| o.rule_id == rule_id) {
+                Some(existing) => existing.roles.push(role),
+                None => overrides.push(AutomodRuleOverrides {
+                    rule_id,
+                    roles: vec![role],
+                }),
+            }
+        }
+
+        overrides
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct RawAutomodRuleOverride {
+    pub rule_id: String,
+    pub role_id: i64,
+    pub is_allowed: bool,
+}
+
 #[derive(Default, Debug, Clone, Copy, sqlx::Type)]
 #[sqlx(type_name = "stickyrolemode")]
 #[sqlx(rename_all = "lowercase")]
 pub enum StickyRoleMode {
+    /// Every role sticks.
     #[default]
     None,
+    /// Only roles in `allowlist_roles` stick.
     Allowlist,
+    /// Every role except the ones in `denylist_roles` sticks.
     Denylist,
 }
 
@@ -232,6 +592,17 @@ pub struct StickyRoleSettings {
     pub is_enabled: bool,
 }
 
+impl StickyRoleSettings {
+    #[must_use]
+    pub fn is_sticky(&self, role_id: RoleId) -> bool {
+        match self.mode {
+            StickyRoleMode::None => true,
+            StickyRoleMode::Allowlist => self.allowlist_roles.contains(&role_id),
+            StickyRoleMode::Denylist => !self.denylist_roles.contains(&role_id),
+        }
+    }
+}
+
 impl From<RawStickyRoleSettings> for StickyRoleSettings {
     fn from(raw: RawStickyRoleSettings) -> Self {
         StickyRoleSettings {
@@ -273,6 +644,24 @@ pub struct CotdRoleSettings {
     pub rotation_time: Time,
 }
 
+impl CotdRoleSettings {
+    /// Settings for a newly added role, matching the column defaults.
+    #[must_use]
+    pub fn new(role_id: RoleId) -> Self {
+        CotdRoleSettings {
+            role_id,
+            colour_mode: ColourMode::Random,
+            icon_pairing_mode: IconPairingMode::Paired,
+            colours: vec![],
+            icons: vec![],
+            svg_target_colour: None,
+            rotation_time: Time::MIDNIGHT,
+            __generated_flags: CotdRoleSettingsGeneratedFlags::empty(),
+        }
+        .set_is_enabled(true)
+    }
+}
+
 impl From<RawCotdRoleSettings> for CotdRoleSettings {
     fn from(raw: RawCotdRoleSettings) -> Self {
         let colours_vec: Vec<Vec<u32>> = serde_json::from_value(raw.colours).expect("valid json");
@@ -308,6 +697,27 @@ impl From<RawCotdRoleSettings> for CotdRoleSettings {
     }
 }
 
+/// The inverse of the `colours` conversion in `From<RawCotdRoleSettings>`.
+#[must_use]
+pub fn colours_to_json(colours: &[RoleColours]) -> serde_json::Value {
+    let colours_vec: Vec<Vec<u32>> = colours
+        .iter()
+        .map(|colours| {
+            [
+                Some(colours.primary_colour),
+                colours.secondary_colour,
+                colours.tertiary_colour,
+            ]
+            .into_iter()
+            .flatten()
+            .map(|c| c.0)
+            .collect()
+        })
+        .collect();
+
+    serde_json::json!(colours_vec)
+}
+
 #[derive(sqlx::FromRow)]
 pub struct RawCotdRoleSettings {
     pub role_id: i64,
@@ -321,6 +731,32 @@ pub struct RawCotdRoleSettings {
     pub rotation_time: Time,
 }
 
+pub struct CotdHistoryEntry {
+    pub colour: u32,
+    pub icon: Option<String>,
+    pub assigned_at: Option<OffsetDateTime>,
+    pub assigned_by: Option<UserId>,
+}
+
+impl From<RawCotdHistoryEntry> for CotdHistoryEntry {
+    fn from(raw: RawCotdHistoryEntry) -> Self {
+        CotdHistoryEntry {
+            colour: raw.colour as u32,
+            icon: raw.icon,
+            assigned_at: raw.assigned_at,
+            assigned_by: raw.assigned_by.map(|id| UserId::new(id as u64)),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct RawCotdHistoryEntry {
+    pub colour: i32,
+    pub icon: Option<String>,
+    pub assigned_at: Option<OffsetDateTime>,
+    pub assigned_by: Option<i64>,
+}
+
 #[derive(Debug, Clone, Copy, sqlx::Type)]
 #[sqlx(type_name = "cotdiconpairingmode")]
 #[sqlx(rename_all = "lowercase")]
@@ -336,3 +772,104 @@ pub enum ColourMode {
     Random,
     Static,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "emoteusagetype")]
+#[sqlx(rename_all = "lowercase")]
+pub enum EmoteUsageType {
+    Message,
+    Reaction,
+}
+
+#[test]
+fn test_invalid_regex_trigger() {
+    assert!(Pattern::new("a{1000}{1000}", false).is_err());
+    assert!(Pattern::new("(?=moth)", true).is_ok());
+
+    let raw = RawRegexTrigger {
+        id: 1,
+        channel_id: None,
+        pattern: "(unclosed".to_string(),
+        trigger_context: TriggerContext::TEXT,
+        trigger_metadata: serde_json::json!({ "text": "hi" }),
+        is_recursive: true,
+        is_enabled: true,
+        is_fancy: false,
+    };
+    let invalid = RegexTrigger::try_from(raw).err().unwrap();
+    assert_eq!(invalid.id, 1);
+    assert!(invalid.was_enabled);
+}
+
+#[test]
+fn test_resolve_mod_role_permissions() {
+    let helper = RoleId::new(1);
+    let mod_roles = [ModRole {
+        role_id: helper,
+        permissions: ModRolePermissions::MANAGE_TRIGGERS | ModRolePermissions::VIEW_LOGS,
+    }];
+
+    assert_eq!(
+        ModRolePermissions::resolve(&mod_roles, &[], Permissions::empty()),
+        ModRolePermissions::empty()
+    );
+    assert_eq!(
+        ModRolePermissions::resolve(&mod_roles, &[helper], Permissions::MODERATE_MEMBERS),
+        ModRolePermissions::MANAGE_TRIGGERS
+            | ModRolePermissions::VIEW_LOGS
+            | ModRolePermissions::BYPASS_FILTERS
+    );
+    assert_eq!(
+        ModRolePermissions::resolve(&mod_roles, &[], Permissions::ADMINISTRATOR),
+        ModRolePermissions::all()
+    );
+}
+
+#[test]
+fn test_automod_rule_overrides() {
+    let raw = |rule_id: &str, role_id: i64, is_allowed: bool| RawAutomodRuleOverride {
+        rule_id: rule_id.to_string(),
+        role_id,
+        is_allowed,
+    };
+    let overrides = AutomodRuleOverrides::from_raw(vec![
+        raw("10", 1, true),
+        raw("10", 2, false),
+        raw("20", 1, true),
+        raw("not a snowflake", 1, true),
+    ]);
+    assert_eq!(overrides.len(), 2);
+
+    let rule = &overrides[0];
+    assert!(rule.exempts(&[RoleId::new(1)]));
+    assert!(!rule.exempts(&[RoleId::new(1), RoleId::new(2)]));
+    assert!(!rule.exempts(&[RoleId::new(3)]));
+}
+
+#[test]
+fn test_filter_punishment() {
+    let mut settings = FilterSettings::default();
+    assert_eq!(settings.punishment(1), FilterPunishment::None);
+    assert_eq!(settings.punishment(10), FilterPunishment::None);
+
+    settings.timeout_minutes = 60;
+    assert_eq!(settings.punishment(10), FilterPunishment::Timeout(60));
+
+    settings.timeout_minutes = u32::MAX;
+    assert_eq!(
+        settings.punishment(1),
+        FilterPunishment::Timeout(MAX_FILTER_TIMEOUT_MINUTES)
+    );
+
+    settings.timeout_minutes = 60;
+    settings.escalation = FilterEscalation::Ban;
+    assert_eq!(settings.punishment(1), FilterPunishment::Timeout(60));
+    assert_eq!(settings.punishment(2), FilterPunishment::Timeout(60));
+    assert_eq!(settings.punishment(3), FilterPunishment::Ban);
+    assert_eq!(settings.punishment(4), FilterPunishment::Ban);
+
+    settings.timeout_minutes = 0;
+    settings.escalation = FilterEscalation::Kick;
+    assert_eq!(settings.punishment(2), FilterPunishment::None);
+    assert_eq!(settings.punishment(3), FilterPunishment::Kick);
+}
@@ -1,6 +1,15 @@
-use serenity::all::Permissions;
+use serenity::all::{
+    CreateAllowedMentions, CreateEmbed, CreateEmbedFooter, CreateMessage, FullEvent,
+    GenericChannelId, GuildId, Http, Permissions, Timestamp, UserId,
+};
 
+use std::collections::VecDeque;
 use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::NEGATIVE_COLOR_HEX;
+use crate::structs::{Context, Data};
 
 impl fmt::Display for PermissionErrorType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -37,5 +46,278 @@ pub enum Error {
     Custom(Box<dyn std::error::Error + Send + Sync>),
 }
 
-#[expect(unused_variables, clippy::unused_async)]
-pub async fn event_handler(ctx: &serenity::all::Context, error: Error) {}
+impl Error {
+    /// `anyhow::Error` doesn't implement `std::error::Error`, so it can't go through the blanket `From`.
+    #[must_use]
+    pub fn from_anyhow(e: anyhow::Error) -> Self {
+        Error::Custom(e.into())
+    }
+}
+
+/// How many unexpected errors are forwarded to the owner log channel per window.
+const MAX_REPORTS: usize = 5;
+const REPORT_WINDOW: Duration = Duration::from_secs(60);
+
+static REPORT_LIMITER: Mutex<RateLimiter> =
+    Mutex::new(RateLimiter::new(MAX_REPORTS, REPORT_WINDOW));
+
+/// The channel unexpected errors are forwarded to, `MOTHY_ERROR_LOG_CHANNEL` if set.
+fn error_log_channel() -> Option<GenericChannelId> {
+    std::env::var("MOTHY_ERROR_LOG_CHANNEL")
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|id| *id != 0)
+        .map(GenericChannelId::new)
+}
+
+/// Allows at most `max` events per sliding `window`, counting the ones it turned away.
+pub struct RateLimiter {
+    max: usize,
+    window: Duration,
+    sent: VecDeque<Instant>,
+    suppressed: u32,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub const fn new(max: usize, window: Duration) -> Self {
+        RateLimiter {
+            max,
+            window,
+            sent: VecDeque::new(),
+            suppressed: 0,
+        }
+    }
+
+    /// Returns how many events were suppressed since the last allowed one, or `None` if this one
+    /// is suppressed too.
+    pub fn try_acquire(&mut self, now: Instant) -> Option<u32> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= self.window)
+        {
+            self.sent.pop_front();
+        }
+
+        if self.sent.len() >= self.max {
+            self.suppressed += 1;
+            return None;
+        }
+
+        self.sent.push_back(now);
+        Some(std::mem::take(&mut self.suppressed))
+    }
+}
+
+/// Where an error happened, for logs and reports.
+#[derive(Default)]
+pub struct ErrorContext {
+    /// The command or event that failed.
+    pub source: String,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: Option<GenericChannelId>,
+    pub user_id: Option<UserId>,
+}
+
+impl ErrorContext {
+    fn log_expected(&self, error: &Error) {
+        tracing::warn!(
+            source = %self.source,
+            guild_id = self.guild_id.map(GuildId::get),
+            channel_id = self.channel_id.map(GenericChannelId::get),
+            user_id = self.user_id.map(UserId::get),
+            error = %error,
+            "Missing permissions"
+        );
+    }
+
+    fn log_unexpected(&self, error: &Error) {
+        tracing::error!(
+            source = %self.source,
+            guild_id = self.guild_id.map(GuildId::get),
+            channel_id = self.channel_id.map(GenericChannelId::get),
+            user_id = self.user_id.map(UserId::get),
+            error = ?error,
+            "Unexpected error"
+        );
+    }
+
+    fn from_command(ctx: Context<'_>) -> Self {
+        ErrorContext {
+            source: format!("command `{}`", ctx.command().qualified_name),
+            guild_id: ctx.guild_id(),
+            channel_id: Some(ctx.channel_id()),
+            user_id: Some(ctx.author().id),
+        }
+    }
+
+    /// Takes the IDs from the events that have handlers, others only get their name.
+    fn from_event(event: &FullEvent) -> Self {
+        let (guild_id, channel_id, user_id) = match event {
+            FullEvent::Message { new_message, .. } => (
+                new_message.guild_id,
+                Some(new_message.channel_id),
+                Some(new_message.author.id),
+            ),
+            FullEvent::MessageDelete {
+                channel_id,
+                guild_id,
+                ..
+            } => (*guild_id, Some(*channel_id), None),
+            FullEvent::ReactionAdd {
+                add_reaction: reaction,
+                ..
+            }
+            | FullEvent::ReactionRemove {
+                removed_reaction: reaction,
+                ..
+            } => (
+                reaction.guild_id,
+                Some(reaction.channel_id),
+                reaction.user_id,
+            ),
+            FullEvent::GuildCreate { guild, .. } => (Some(guild.id), None, None),
+            FullEvent::GuildMemberAddition { new_member, .. } => {
+                (Some(new_member.guild_id), None, Some(new_member.user.id))
+            }
+            FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
+                (Some(*guild_id), None, Some(user.id))
+            }
+            FullEvent::GuildMemberUpdate { event, .. } => {
+                (Some(event.guild_id), None, Some(event.user.id))
+            }
+            FullEvent::AutoModActionExecution { execution, .. } => (
+                Some(execution.guild_id),
+                execution.channel_id.map(Into::into),
+                Some(execution.user_id),
+            ),
+            FullEvent::VoiceStateUpdate { new, .. } => (
+                new.guild_id,
+                new.channel_id.map(Into::into),
+                Some(new.user_id),
+            ),
+            _ => (None, None, None),
+        };
+
+        ErrorContext {
+            source: format!("event `{}`", event.snake_case_name()),
+            guild_id,
+            channel_id,
+            user_id,
+        }
+    }
+}
+
+/// Handles errors from event handlers, missing permissions are expected and only logged.
+pub async fn event_handler(ctx: &serenity::all::Context, event: &FullEvent, error: Error) {
+    let error_context = ErrorContext::from_event(event);
+
+    match &error {
+        Error::Permissions(_) => error_context.log_expected(&error),
+        Error::Custom(_) => report(&ctx.http, &error_context, &error).await,
+    }
+}
+
+/// Handles framework errors, replying to the user and reporting anything unexpected.
+pub async fn command_handler(error: poise::FrameworkError<'_, Data, Error>) {
+    match error {
+        poise::FrameworkError::Command { error, ctx, .. } => {
+            let error_context = ErrorContext::from_command(ctx);
+            let content = match &error {
+                Error::Permissions(e) => {
+                    error_context.log_expected(&error);
+                    e.to_string()
+                }
+                Error::Custom(_) => {
+                    report(ctx.http(), &error_context, &error).await;
+                    "Something went wrong while running this command, it has been reported."
+                        .to_string()
+                }
+            };
+
+            let _ = ctx
+                .send(
+                    poise::CreateReply::default()
+                        .content(content)
+                        .ephemeral(true),
+                )
+                .await;
+        }
+        // argument, check and cooldown errors are the user's, poise already explains those.
+        error => {
+            if let Err(e) = poise::builtins::on_error(error).await {
+                tracing::error!(error = %e, "Error while handling error");
+            }
+        }
+    }
+}
+
+/// Logs an unexpected error and forwards it to the owner log channel unless rate limited.
+async fn report(http: &Http, error_context: &ErrorContext, error: &Error) {
+    error_context.log_unexpected(error);
+
+    let Some(channel_id) = error_log_channel() else {
+        return;
+    };
+    let Some(suppressed) = REPORT_LIMITER.lock().unwrap().try_acquire(Instant::now()) else {
+        return;
+    };
+
+    let details: String = format!("{error:?}").chars().take(3900).collect();
+
+    let mut embed = CreateEmbed::new()
+        .colour(NEGATIVE_COLOR_HEX)
+        .title("Error")
+        .description(format!("```\n{}\n```", details.replace('`', "\\`")))
+        .field("Source", &error_context.source, false)
+        .timestamp(Timestamp::now());
+    if let Some(guild_id) = error_context.guild_id {
+        embed = embed.field("Guild", guild_id.to_string(), true);
+    }
+    if let Some(channel_id) = error_context.channel_id {
+        embed = embed.field("Channel", format!("<#{channel_id}>"), true);
+    }
+    if let Some(user_id) = error_context.user_id {
+        embed = embed.field("User", format!("<@{user_id}>"), true);
+    }
+    if suppressed != 0 {
+        embed = embed.footer(CreateEmbedFooter::new(format!(
+            "{suppressed} earlier errors were not reported"
+        )));
+    }
+
+    if let Err(e) = channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await
+    {
+        tracing::error!(error = %e, "Failed to report error");
+    }
+}
+
+#[test]
+fn test_rate_limiter() {
+    let start = Instant::now();
+    let mut limiter = RateLimiter::new(2, Duration::from_secs(60));
+
+    assert_eq!(limiter.try_acquire(start), Some(0));
+    assert_eq!(limiter.try_acquire(start), Some(0));
+    assert_eq!(limiter.try_acquire(start + Duration::from_secs(30)), None);
+    assert_eq!(limiter.try_acquire(start + Duration::from_secs(40)), None);
+    assert_eq!(
+        limiter.try_acquire(start + Duration::from_secs(60)),
+        Some(2)
+    );
+    assert_eq!(
+        limiter.try_acquire(start + Duration::from_secs(61)),
+        Some(0)
+    );
+    assert_eq!(limiter.try_acquire(start + Duration::from_secs(62)), None);
+}
@@ -1,7 +1,12 @@
+pub mod autoresponse;
+pub mod cotd;
 pub mod database;
 pub mod database_models;
 pub mod error;
+pub mod logging;
 pub mod moth_data;
+pub mod ocr;
+pub mod redirects;
 pub mod regex_filters;
 pub mod score_data;
 pub mod structs;
@@ -0,0 +1,202 @@
+use std::fmt;
+
+use mothy_ansi::{
+    BLUE, CYAN, DIM, GREEN, HI_BLACK, HI_RED, MAGENTA, RED, RESET, YELLOW, from_colour,
+};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Used when `MOTHY_LOG` isn't set, the libraries are far too chatty at info.
+const DEFAULT_FILTER: &str = "info,serenity=warn,poise=warn,sqlx=warn";
+/// How many rotated log files are kept when `MOTHY_LOG_MAX_FILES` isn't set.
+const DEFAULT_MAX_FILES: usize = 14;
+
+/// Sets up logging to the console, and to daily rotated JSON lines files in `MOTHY_LOG_DIR` if set.
+///
+/// The returned guard flushes the file logs when dropped, so it should live as long as the bot.
+///
+/// # Panics
+///
+/// Will panic if `MOTHY_LOG_DIR` is set but the log files can't be created there.
+#[must_use]
+pub fn init() -> Option<WorkerGuard> {
+    let filter =
+        || EnvFilter::try_from_env("MOTHY_LOG").unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    let console = tracing_subscriber::fmt::layer()
+        .event_format(ConsoleFormat)
+        .with_filter(filter());
+
+    let (file, guard) = match std::env::var("MOTHY_LOG_DIR") {
+        Ok(dir) => {
+            let max_files = std::env::var("MOTHY_LOG_MAX_FILES")
+                .ok()
+                .and_then(|max| max.parse().ok())
+                .unwrap_or(DEFAULT_MAX_FILES);
+            let appender = RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix("mothy")
+                .filename_suffix("jsonl")
+                .max_log_files(max_files)
+                .build(dir)
+                .expect("Could not create log files in MOTHY_LOG_DIR.");
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+
+            let layer = tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(false)
+                .with_writer(writer)
+                .with_filter(filter());
+            (Some(layer), Some(guard))
+        }
+        Err(_) => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(console)
+        .with(file)
+        .init();
+
+    guard
+}
+
+/// The fields of one event, in the order they were recorded.
+#[derive(Default)]
+struct ConsoleFields {
+    message: String,
+    /// The role colour of `author`, 0 when they have none.
+    author_colour: u32,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl ConsoleFields {
+    fn get(&self, name: &str) -> &str {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map_or("", |(_, value)| value.as_str())
+    }
+
+    fn author(&self) -> String {
+        let colour = from_colour(self.author_colour);
+        format!(
+            "{}{}{RESET}",
+            colour.as_deref().unwrap_or(""),
+            self.get("author")
+        )
+    }
+
+    fn insert(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = value,
+            name => self.fields.push((name, value)),
+        }
+    }
+}
+
+impl Visit for ConsoleFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "author_colour" {
+            self.author_colour = u32::try_from(value).unwrap_or_default();
+        } else {
+            self.insert(field, value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{value:?}"));
+    }
+}
+
+/// Prints Discord activity like the chat it came from and everything else as a levelled line.
+struct ConsoleFormat;
+
+impl<S, N> FormatEvent<S, N> for ConsoleFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        _: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut fields = ConsoleFields::default();
+        event.record(&mut fields);
+
+        let guild = fields.get("guild");
+        let channel = fields.get("channel");
+        let message = &fields.message;
+
+        match fields.get("event") {
+            "message" => writeln!(
+                writer,
+                "{HI_BLACK}[{guild}] [#{channel}]{RESET} {}: {message}{RESET}{CYAN}{}{}{RESET}",
+                fields.author(),
+                fields.get("attachments"),
+                fields.get("embeds"),
+            ),
+            "message_filtered" => writeln!(
+                writer,
+                "{HI_RED}REGEX DELETED [{guild}] [#{channel}]{RESET} {}: {message}{RESET}",
+                fields.author(),
+            ),
+            "message_filter_failed" => writeln!(
+                writer,
+                "FAILED TO REGEX DELETE {HI_RED}[{guild}] [#{channel}]{RESET} {}: {message}{RESET}",
+                fields.author(),
+            ),
+            "message_delete" => writeln!(
+                writer,
+                "{HI_RED}{DIM}[{guild}] [#{channel}] A message from {RESET}{}{HI_RED}{DIM} was \
+                 deleted: {message}{}{}{RESET}",
+                fields.author(),
+                fields.get("attachments"),
+                fields.get("embeds"),
+            ),
+            "member_join" | "member_leave" => writeln!(
+                writer,
+                "{YELLOW}[{guild}] {} (ID:{}) {message}{RESET}",
+                fields.get("author"),
+                fields.get("user_id"),
+            ),
+            _ => {
+                let metadata = event.metadata();
+                let level_colour = match *metadata.level() {
+                    Level::ERROR => RED,
+                    Level::WARN => YELLOW,
+                    Level::INFO => GREEN,
+                    Level::DEBUG => BLUE,
+                    Level::TRACE => MAGENTA,
+                };
+
+                write!(
+                    writer,
+                    "{DIM}{}{RESET} {level_colour}{:>5}{RESET} {DIM}{}:{RESET} {message}",
+                    chrono::Local::now().format("%H:%M:%S"),
+                    metadata.level(),
+                    metadata.target(),
+                )?;
+                for (name, value) in &fields.fields {
+                    write!(writer, " {DIM}{name}={RESET}{value}")?;
+                }
+                writeln!(writer)
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::{io::AsyncWriteExt, process::Command, sync::Semaphore};
+
+/// OCR is CPU heavy, so only this many images are processed at once.
+static OCR_PERMITS: Semaphore = Semaphore::const_new(2);
+/// Images larger than this are not worth the time OCR would take.
+pub const MAX_IMAGE_SIZE: u32 = 8 * 1024 * 1024;
+/// A hung tesseract would otherwise hold a permit forever.
+const OCR_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The OCR engine binary, `MOTHY_TESSERACT` or `tesseract` from the path.
+fn tesseract_path() -> String {
+    std::env::var("MOTHY_TESSERACT").unwrap_or_else(|_| "tesseract".to_string())
+}
+
+/// Extracts the text from an image with tesseract.
+pub async fn recognise(image: &[u8]) -> anyhow::Result<String> {
+    if image.len() > MAX_IMAGE_SIZE as usize {
+        anyhow::bail!("image is {} bytes, over the OCR limit", image.len());
+    }
+    let _permit = OCR_PERMITS.acquire().await?;
+
+    let mut child = Command::new(tesseract_path())
+        .args(["stdin", "stdout", "--psm", "3"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // tesseract reads all of stdin before writing anything, so this can't deadlock.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let run = async move {
+        stdin.write_all(image).await?;
+        drop(stdin);
+        child.wait_with_output().await
+    };
+
+    // dropping the child on timeout kills it, see `kill_on_drop`.
+    let Ok(output) = tokio::time::timeout(OCR_TIMEOUT, run).await else {
+        anyhow::bail!("tesseract took longer than {}s", OCR_TIMEOUT.as_secs());
+    };
+    let output = output?;
+    if !output.status.success() {
+        anyhow::bail!("tesseract exited with {}", output.status);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TextStats {
+    pub word_count: u16,
+    pub line_count: u16,
+}
+
+/// Counts the words and non-blank lines of recognised text, saturating at `u16::MAX`.
+#[must_use]
+pub fn text_stats(text: &str) -> TextStats {
+    let lines = text.lines().filter(|line| !line.trim().is_empty());
+    let (word_count, line_count) = lines.fold((0usize, 0usize), |(words, lines), line| {
+        (words + line.split_whitespace().count(), lines + 1)
+    });
+
+    TextStats {
+        word_count: u16::try_from(word_count).unwrap_or(u16::MAX),
+        line_count: u16::try_from(line_count).unwrap_or(u16::MAX),
+    }
+}
+
+#[test]
+fn test_text_stats() {
+    let stats = text_stats("FREE NITRO\n\n  claim at  the link below \n\x0c");
+    assert_eq!(
+        stats,
+        TextStats {
+            word_count: 7,
+            line_count: 2,
+        }
+    );
+}
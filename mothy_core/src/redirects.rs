@@ -0,0 +1,412 @@
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::LOCATION;
+
+use crate::structs::Redirects;
+
+/// How long a resolved link is remembered, shorteners rarely change where a link goes.
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+/// How long a link that couldn't be followed to the end is remembered, so a slow shortener
+/// doesn't get its links through for the whole `CACHE_TTL`.
+const FAILURE_CACHE_TTL: Duration = Duration::from_secs(30);
+const MAX_CACHED: usize = 10_000;
+
+pub type RedirectFuture<'a> =
+    Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send + 'a>>;
+
+/// Looks up where a link redirects to.
+pub trait RedirectResolver: Send + Sync {
+    /// The absolute link `url` redirects to, `None` if it doesn't redirect.
+    fn redirect<'a>(&'a self, url: &'a str) -> RedirectFuture<'a>;
+}
+
+/// Sends a HEAD request and reads the `Location` header, without following it.
+pub struct HttpResolver {
+    client: reqwest::Client,
+}
+
+impl HttpResolver {
+    /// # Panics
+    ///
+    /// Will panic if the HTTP client can't be built.
+    #[must_use]
+    pub fn new() -> Self {
+        // a proxy would resolve names itself, going around `PublicResolver`.
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicResolver))
+            .user_agent("mothy (https://github.com/Kuuuube/mothy)")
+            .build()
+            .expect("Could not build the redirect HTTP client.");
+
+        HttpResolver { client }
+    }
+}
+
+impl Default for HttpResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RedirectResolver for HttpResolver {
+    fn redirect<'a>(&'a self, url: &'a str) -> RedirectFuture<'a> {
+        Box::pin(async move {
+            let response = self.client.head(url).send().await?;
+            if !response.status().is_redirection() {
+                return Ok(None);
+            }
+            let Some(location) = response.headers().get(LOCATION) else {
+                return Ok(None);
+            };
+
+            // relative locations are relative to the link that redirected.
+            let location = response.url().join(location.to_str()?)?;
+            Ok(Some(location.to_string()))
+        })
+    }
+}
+
+/// Resolves hostnames to their public addresses only, so a link can't reach the bot's network
+/// through a name that points inside it.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_global(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public addresses", name.as_str()).into());
+            }
+
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+struct CachedDestination {
+    destination: Option<String>,
+    expires_at: Instant,
+}
+
+/// Follows links through shorteners and redirects, remembering where they went.
+pub struct RedirectFollower {
+    resolver: Box<dyn RedirectResolver>,
+    cache: DashMap<String, CachedDestination>,
+}
+
+impl RedirectFollower {
+    #[must_use]
+    pub fn new(resolver: impl RedirectResolver + 'static) -> Self {
+        RedirectFollower {
+            resolver: Box::new(resolver),
+            cache: DashMap::new(),
+        }
+    }
+
+    /// Where `url` ends up after at most `max_hops` redirects, `None` if it doesn't redirect or
+    /// that couldn't be found out in time.
+    pub async fn resolve(&self, url: &str, settings: &Redirects) -> Option<String> {
+        if let Some(cached) = self.cache.get(url)
+            && Instant::now() < cached.expires_at
+        {
+            return cached.destination.clone();
+        }
+
+        let timeout = Duration::from_millis(settings.timeout_ms);
+        let (destination, complete) =
+            tokio::time::timeout(timeout, self.follow(url, settings.max_hops))
+                .await
+                .unwrap_or_else(|_| {
+                    tracing::debug!(url, "Timed out following redirects");
+                    (None, false)
+                });
+
+        let now = Instant::now();
+        if self.cache.len() >= MAX_CACHED {
+            self.cache.retain(|_, cached| now < cached.expires_at);
+            if self.cache.len() >= MAX_CACHED {
+                self.cache.clear();
+            }
+        }
+        let ttl = if complete {
+            CACHE_TTL
+        } else {
+            FAILURE_CACHE_TTL
+        };
+        self.cache.insert(
+            url.to_string(),
+            CachedDestination {
+                destination: destination.clone(),
+                expires_at: now + ttl,
+            },
+        );
+
+        destination
+    }
+
+    /// Where `url` ends up, and whether that's definitely the end, which it isn't if a request
+    /// failed on the way.
+    async fn follow(&self, url: &str, max_hops: usize) -> (Option<String>, bool) {
+        let mut current = url.to_string();
+        let mut complete = true;
+        for _ in 0..max_hops {
+            if !is_followable(&current) {
+                break;
+            }
+            match self.resolver.redirect(&current).await {
+                Ok(Some(next)) => current = next,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::debug!(url = %current, error = %e, "Failed to follow redirect");
+                    complete = false;
+                    break;
+                }
+            }
+        }
+
+        ((current != url).then_some(current), complete)
+    }
+}
+
+/// Only public http(s) links are requested, so links can't be used to poke at the bot's network.
+///
+/// Hostnames are checked again when they're resolved, see [`PublicResolver`].
+fn is_followable(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<IpAddr>() {
+        Ok(ip) => is_global(ip),
+        Err(_) => host != "localhost" && !host.ends_with(".localhost"),
+    }
+}
+
+/// Whether an address is reachable on the public internet, leaving out private, shared,
+/// loopback, link local and reserved ranges.
+fn is_global(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_global_v4(ip),
+        IpAddr::V6(ip) => is_global_v6(ip),
+    }
+}
+
+fn is_global_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "this network", carrier grade NAT, IETF protocol assignments, benchmarking and reserved.
+        || a == 0
+        || (a == 100 && b & 0xc0 == 64)
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && b & 0xfe == 18)
+        || a >= 240)
+}
+
+fn is_global_v6(ip: Ipv6Addr) -> bool {
+    // IPv4-mapped and -compatible addresses go to the IPv4 address they carry.
+    if let Some(ip) = ip.to_ipv4() {
+        return is_global_v4(ip);
+    }
+
+    let segments = ip.segments();
+    let embedded_v4 = |high: u16, low: u16| {
+        let [a, b] = high.to_be_bytes();
+        let [c, d] = low.to_be_bytes();
+        Ipv4Addr::new(a, b, c, d)
+    };
+    // so do NAT64 (64:ff9b::/96) and 6to4 (2002::/16) addresses, through a gateway.
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        return is_global_v4(embedded_v4(segments[6], segments[7]));
+    }
+    if segments[0] == 0x2002 {
+        return is_global_v4(embedded_v4(segments[1], segments[2]));
+    }
+
+    let segment = segments[0];
+    !(ip.is_loopback()
+        // local use NAT64, which could go anywhere.
+        || (segment == 0x64 && segments[1] == 0xff9b && segments[2] == 1)
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || segment & 0xfe00 == 0xfc00
+        || segment & 0xffc0 == 0xfe80
+        || (segment == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[test]
+fn test_is_followable() {
+    assert!(is_followable("https://bit.ly/abc"));
+    assert!(is_followable("http://1.1.1.1/"));
+    assert!(!is_followable("ftp://bit.ly/abc"));
+    assert!(!is_followable("http://localhost:8080/"));
+    assert!(!is_followable("http://127.0.0.1/"));
+    assert!(!is_followable("http://192.168.1.1/admin"));
+    assert!(!is_followable("http://[::1]/"));
+    assert!(!is_followable("http://[::ffff:127.0.0.1]/"));
+    assert!(!is_followable("http://[::ffff:10.0.0.1]/"));
+    assert!(!is_followable("http://100.64.0.1/"));
+    assert!(!is_followable("http://0.0.0.0/"));
+    assert!(!is_followable("http://2130706433/"));
+    assert!(!is_followable("http://[fd00::1]/"));
+    assert!(!is_followable("http://[64:ff9b::7f00:1]/"));
+    assert!(!is_followable("http://[64:ff9b::192.168.0.1]/"));
+    assert!(!is_followable("http://[64:ff9b:1::1]/"));
+    assert!(!is_followable("http://[2002:a00:1::]/"));
+    assert!(is_followable("http://[64:ff9b::1.1.1.1]/"));
+    assert!(is_followable("http://[2002:101:101::]/"));
+    assert!(is_followable("http://[2606:4700:4700::1111]/"));
+    assert!(!is_followable("not a link"));
+}
+
+#[tokio::test]
+async fn test_redirect_follower() {
+    use std::collections::HashMap;
+
+    struct StubResolver(HashMap<&'static str, &'static str>);
+
+    impl RedirectResolver for StubResolver {
+        fn redirect<'a>(&'a self, url: &'a str) -> RedirectFuture<'a> {
+            let next = self.0.get(url).map(ToString::to_string);
+            Box::pin(async move {
+                if url.ends_with("/down") {
+                    anyhow::bail!("connection refused");
+                }
+                Ok(next)
+            })
+        }
+    }
+
+    let follower = RedirectFollower::new(StubResolver(HashMap::from([
+        ("https://bit.ly/a", "https://t.co/b"),
+        ("https://t.co/b", "https://evil.ru/claim"),
+        ("https://bit.ly/loop", "https://bit.ly/loop"),
+        ("https://bit.ly/local", "http://127.0.0.1/"),
+        ("http://127.0.0.1/", "https://evil.ru/"),
+        ("https://bit.ly/flaky", "https://t.co/down"),
+    ])));
+    let settings = Redirects {
+        max_hops: 2,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        follower.resolve("https://bit.ly/a", &settings).await,
+        Some("https://evil.ru/claim".to_string())
+    );
+    assert_eq!(follower.resolve("https://evil.ru/", &settings).await, None);
+    assert_eq!(
+        follower.resolve("https://bit.ly/loop", &settings).await,
+        None
+    );
+    // stops at the local address instead of requesting it.
+    assert_eq!(
+        follower.resolve("https://bit.ly/local", &settings).await,
+        Some("http://127.0.0.1/".to_string())
+    );
+
+    // a failed request isn't remembered for long, the link may well go further.
+    assert_eq!(
+        follower.resolve("https://bit.ly/flaky", &settings).await,
+        Some("https://t.co/down".to_string())
+    );
+    assert!(
+        follower
+            .cache
+            .get("https://bit.ly/flaky")
+            .unwrap()
+            .expires_at
+            <= Instant::now() + FAILURE_CACHE_TTL
+    );
+    assert!(
+        follower.cache.get("https://bit.ly/a").unwrap().expires_at
+            > Instant::now() + FAILURE_CACHE_TTL
+    );
+
+    let settings = Redirects {
+        max_hops: 1,
+        ..Default::default()
+    };
+    // cached from the first lookup.
+    assert_eq!(
+        follower.resolve("https://bit.ly/a", &settings).await,
+        Some("https://evil.ru/claim".to_string())
+    );
+    assert_eq!(
+        follower.resolve("https://t.co/b", &settings).await,
+        Some("https://evil.ru/claim".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_http_resolver() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(&stream);
+            reader.read_line(&mut request_line).unwrap();
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                header.clear();
+            }
+
+            let response = match request_line.split(' ').nth(1).unwrap_or_default() {
+                "/short" => "HTTP/1.1 301 Moved Permanently\r\nLocation: /hop\r\n",
+                "/hop" => "HTTP/1.1 302 Found\r\nLocation: https://evil.ru/claim\r\n",
+                _ => "HTTP/1.1 200 OK\r\n",
+            };
+            write!(
+                stream,
+                "{response}Content-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+        }
+    });
+
+    let resolver = HttpResolver::new();
+    let url = |path: &str| format!("http://{address}{path}");
+
+    assert_eq!(
+        resolver.redirect(&url("/short")).await.unwrap(),
+        Some(url("/hop"))
+    );
+    assert_eq!(
+        resolver.redirect(&url("/hop")).await.unwrap(),
+        Some("https://evil.ru/claim".to_string())
+    );
+    assert_eq!(resolver.redirect(&url("/end")).await.unwrap(), None);
+
+    // addresses are only checked when a name is resolved, and localhost isn't public.
+    let local = format!("http://localhost:{}/short", address.port());
+    assert!(resolver.redirect(&local).await.is_err());
+}
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 use serde::{Deserialize, Serialize};
-use serenity::all::{GenericChannelId, GuildId, RoleId};
+use serenity::all::{GenericChannelId, GuildId, UserId};
 
 use crate::error::Error;
+use crate::redirects::RedirectFollower;
+use crate::regex_filters::LinkBlacklist;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
 pub type Command = poise::Command<Data, Error>;
 
@@ -13,7 +16,8 @@ pub struct Data {
     pub database: crate::database::Database,
     pub james_scores: Vec<ScoresData>,
     pub regex_filters: RegexFilters,
-    pub config: MothyConfig,
+    pub redirects: RedirectFollower,
+    pub config: RwLock<MothyConfig>,
     pub command_data: CommandData,
     pub moth_data: MothData,
 }
@@ -32,43 +36,37 @@ pub struct CommandData {
 
 pub struct RegexFilters {
     pub links_detector: regex::Regex,
-    pub links_blacklist: Vec<regex::Regex>,
+    /// Swapped out whole when the blacklist changes, see `RegexFilters::reload`.
+    pub links_blacklist: RwLock<Arc<LinkBlacklist>>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
 pub struct MothyConfig {
+    #[serde(default)]
     pub events: Events,
-    pub filters: Filters,
-    pub logs: Logs,
+    /// Links are only followed through redirects when this is set.
+    #[serde(default)]
+    pub redirects: Option<Redirects>,
 }
 
 impl MothyConfig {
+    /// Loads the config, falling back to the defaults if it can't be read.
     #[must_use]
     pub fn new() -> Self {
-        MothyConfig {
-            events: Events::default(),
-            filters: Filters {
-                // only allow filters to apply to kuuube server and test server
-                filters_allowed_guilds: vec![902907712441040926.into(), 529423189860679702.into()],
-                // regular role on kuuube server, test role on test server
-                filter_bypass_roles: vec![1001489392457760828.into(), 1440516379840090345.into()],
-            },
-            logs: Logs {
-                // kuuube server join logs channel, test server logs channel
-                mothy_join_logs_channel: HashMap::from([
-                    (902907712441040926.into(), 920370368135442442.into()),
-                    (529423189860679702.into(), 894927450063138816.into()),
-                ]),
-                // kuuube server blacklist logs channel, test server logs channel
-                mothy_blacklist_logs_channel: HashMap::from([
-                    (902907712441040926.into(), 917776727801995304.into()),
-                    (529423189860679702.into(), 894927450063138816.into()),
-                ]),
-                // kuuube server voice logs channel, test server logs channel
-                mothy_voice_logs_channel: HashMap::from([
-                    (902907712441040926.into(), 1443106469644996668.into()),
-                    (529423189860679702.into(), 894927450063138816.into()),
-                ]),
-            },
+        Self::load().unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to load the config, using the defaults");
+            MothyConfig::default()
+        })
+    }
+
+    /// Reads the JSON config at `MOTHY_CONFIG` (`config.json` by default), a missing file is the
+    /// default config.
+    pub fn load() -> anyhow::Result<Self> {
+        let path = std::env::var("MOTHY_CONFIG").unwrap_or_else(|_| "config.json".to_string());
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(MothyConfig::default()),
+            Err(e) => Err(e.into()),
         }
     }
 }
@@ -80,16 +78,55 @@ pub struct Events {
     pub guild_name_override: Option<HashMap<GuildId, String>>,
 }
 
+impl Events {
+    /// Whether activity by `user_id` in a channel should be printed, `channels` being the channel
+    /// followed by its parents so ignoring a category covers everything in it.
+    #[must_use]
+    pub fn should_log(&self, channels: &[GenericChannelId], user_id: Option<UserId>) -> bool {
+        if let Some(no_log_channels) = &self.no_log_channels
+            && channels
+                .iter()
+                .any(|id| no_log_channels.contains(&id.get()))
+        {
+            return false;
+        }
+
+        if let Some(no_log_users) = &self.no_log_users
+            && user_id.is_some_and(|id| no_log_users.contains(&id.get()))
+        {
+            return false;
+        }
+
+        true
+    }
+
+    #[must_use]
+    pub fn guild_name_override(&self, guild_id: GuildId) -> Option<&str> {
+        self.guild_name_override
+            .as_ref()?
+            .get(&guild_id)
+            .map(String::as_str)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct Filters {
-    pub filters_allowed_guilds: Vec<GuildId>,
-    pub filter_bypass_roles: Vec<RoleId>,
+#[serde(default)]
+pub struct Redirects {
+    /// How long following one link may take in total.
+    pub timeout_ms: u64,
+    pub max_hops: usize,
+    /// How many links in a message are followed.
+    pub max_links: usize,
 }
 
-pub struct Logs {
-    pub mothy_join_logs_channel: HashMap<GuildId, GenericChannelId>,
-    pub mothy_blacklist_logs_channel: HashMap<GuildId, GenericChannelId>,
-    pub mothy_voice_logs_channel: HashMap<GuildId, GenericChannelId>,
+impl Default for Redirects {
+    fn default() -> Self {
+        Redirects {
+            timeout_ms: 2000,
+            max_hops: 5,
+            max_links: 3,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -239,3 +276,25 @@ pub struct Covers {
     #[serde(rename = "slimcover@2x")]
     pub slimcover_2x: String,
 }
+
+#[test]
+fn test_events_should_log() {
+    let events: Events = serde_json::from_str(
+        r#"{
+            "no_log_channels": [10],
+            "no_log_users": [20],
+            "guild_name_override": { "1": "Home" }
+        }"#,
+    )
+    .unwrap();
+
+    let channel = GenericChannelId::new(11);
+    let category = GenericChannelId::new(10);
+    assert!(events.should_log(&[channel], Some(UserId::new(21))));
+    assert!(!events.should_log(&[channel, category], Some(UserId::new(21))));
+    assert!(!events.should_log(&[channel], Some(UserId::new(20))));
+    assert!(events.should_log(&[channel], None));
+
+    assert_eq!(events.guild_name_override(GuildId::new(1)), Some("Home"));
+    assert_eq!(events.guild_name_override(GuildId::new(2)), None);
+}
@@ -0,0 +1,176 @@
+use mothy_core::{
+    NEUTRAL_ACTION_COLOR_HEX, database_models::GuildFeatures, error::Error, structs::Data,
+};
+use serenity::all::{
+    Action, ActionExecution, ChannelId, Context, CreateAllowedMentions, CreateEmbed,
+    CreateEmbedAuthor, CreateEmbedFooter, CreateMessage, CreateWebhook, ExecuteWebhook,
+    GenericChannelId, GuildId, Member, ThreadId, Timestamp, Webhook,
+};
+
+/// The name of the webhook exempted messages are reposted with, it is reused once created.
+const WEBHOOK_NAME: &str = "Mothy Automod";
+/// Automod checks messages of up to 4000 characters, webhooks can only send 2000.
+const MAX_REPOST_LEN: usize = 2000;
+/// How much of the blocked message is logged, leaving room in the embed description.
+const MAX_LOGGED_LEN: usize = 3500;
+
+/// Reposts a message Discord's automod blocked if an override exempts the author, and logs it.
+pub async fn action_execution(
+    ctx: &Context,
+    execution: &ActionExecution,
+    data: &Data,
+) -> Result<(), Error> {
+    // an execution arrives for every action of a rule, only blocking loses the message.
+    if !matches!(execution.action, Action::BlockMessage { .. }) {
+        return Ok(());
+    }
+    let Some(channel_id) = execution.channel_id else {
+        return Ok(());
+    };
+
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(execution.guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if !guild_settings
+        .features
+        .contains(GuildFeatures::AUTOMODERATION)
+    {
+        return Ok(());
+    }
+
+    let Some(overrides) = guild_settings
+        .automod_rule_overrides
+        .iter()
+        .find(|o| o.rule_id == execution.rule_id)
+    else {
+        return Ok(());
+    };
+
+    let member = execution
+        .guild_id
+        .member(&ctx.http, execution.user_id)
+        .await?;
+    if !overrides.exempts(&member.roles) {
+        return Ok(());
+    }
+
+    let outcome = if execution.content.is_empty() {
+        "Not reposted, the message had no text".to_string()
+    } else if execution.content.chars().count() > MAX_REPOST_LEN {
+        format!("Not reposted, the message was over {MAX_REPOST_LEN} characters")
+    } else {
+        match repost(
+            ctx,
+            execution.guild_id,
+            channel_id,
+            &member,
+            &execution.content,
+        )
+        .await
+        {
+            Ok(()) => "Reposted".to_string(),
+            Err(e) => format!("Not reposted: {e}"),
+        }
+    };
+
+    let Some(logs_channel) = guild_settings.log_settings.blacklist_logs_channel_id else {
+        return Ok(());
+    };
+
+    let content = if execution.content.is_empty() {
+        "(No message content)".to_string()
+    } else {
+        let mut content = execution.content.replace('`', "\\`");
+        if content.chars().count() > MAX_LOGGED_LEN {
+            content = content.chars().take(MAX_LOGGED_LEN - 1).collect();
+            content.push('…');
+        }
+        format!("```\n{content}\n```")
+    };
+    let embed = CreateEmbed::new()
+        .author(CreateEmbedAuthor::new(&member.user.name).icon_url(member.face()))
+        .colour(NEUTRAL_ACTION_COLOR_HEX)
+        .title("Automod Exemption")
+        .description(format!(
+            "Message sent by <@{}> in <#{channel_id}> was blocked by an automod rule they are \
+             exempt from\n{content}",
+            execution.user_id
+        ))
+        .field("Rule", format!("`{}`", execution.rule_id), true)
+        .field("Outcome", outcome, true)
+        .timestamp(Timestamp::now())
+        .footer(CreateEmbedFooter::new(format!("ID: {}", execution.user_id)));
+
+    logs_channel
+        .send_message(
+            &ctx.http,
+            CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Sends `content` to the channel through a webhook impersonating the member.
+async fn repost(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: GenericChannelId,
+    member: &Member,
+    content: &str,
+) -> Result<(), Error> {
+    // webhooks belong to the parent channel, threads are targeted when executing.
+    let (webhook_channel, thread_id) = webhook_target(ctx, guild_id, channel_id);
+    let webhook = get_or_create_webhook(ctx, webhook_channel).await?;
+
+    let mut builder = ExecuteWebhook::new()
+        .content(content)
+        .username(member.display_name())
+        .avatar_url(member.face())
+        .allowed_mentions(CreateAllowedMentions::new());
+    if let Some(thread_id) = thread_id {
+        builder = builder.in_thread(thread_id);
+    }
+
+    webhook.execute(&ctx.http, false, builder).await?;
+    Ok(())
+}
+
+fn webhook_target(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: GenericChannelId,
+) -> (ChannelId, Option<ThreadId>) {
+    let thread = ctx.cache.guild(guild_id).and_then(|guild| {
+        guild
+            .threads
+            .iter()
+            .find(|t| t.id == channel_id.get())
+            .map(|t| (t.parent_id, t.id))
+    });
+
+    match thread {
+        Some((parent_id, thread_id)) => (parent_id, Some(thread_id)),
+        None => (channel_id.expect_channel(), None),
+    }
+}
+
+async fn get_or_create_webhook(ctx: &Context, channel_id: ChannelId) -> Result<Webhook, Error> {
+    let existing = channel_id
+        .webhooks(&ctx.http)
+        .await?
+        .into_iter()
+        .find(|w| w.token.is_some() && w.name.as_deref() == Some(WEBHOOK_NAME));
+
+    match existing {
+        Some(webhook) => Ok(webhook),
+        None => Ok(channel_id
+            .create_webhook(&ctx.http, CreateWebhook::new(WEBHOOK_NAME))
+            .await?),
+    }
+}
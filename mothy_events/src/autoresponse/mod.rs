@@ -0,0 +1,113 @@
+use mothy_core::{
+    autoresponse::{channel_ancestry, find_trigger, trigger_applies},
+    database_models::{GuildSettings, RegexTrigger, TriggerContext},
+    error::Error,
+    ocr,
+    structs::Data,
+};
+use serenity::all::{
+    Attachment, Context, CreateAllowedMentions, CreateMessage, GuildId, Message, MessageReference,
+};
+
+/// Replies with the response of the first trigger matching the message, the caller checks the
+/// feature flag.
+///
+/// Image attachments are only run through OCR when a trigger in this channel wants them.
+pub async fn on_message(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    guild_settings: &GuildSettings,
+    msg: &Message,
+) -> Result<(), Error> {
+    if msg.author.bot() || guild_settings.regex_triggers.is_empty() {
+        return Ok(());
+    }
+
+    let ancestry = channel_ancestry(&ctx.cache, guild_id, msg.channel_id);
+    if let Some(trigger) = find_trigger(
+        guild_settings,
+        TriggerContext::TEXT,
+        &ancestry,
+        &msg.content,
+    ) {
+        return respond(ctx, msg, trigger).await;
+    }
+
+    let wants_ocr = guild_settings
+        .regex_triggers
+        .iter()
+        .any(|trigger| trigger_applies(trigger, TriggerContext::OCR, &ancestry));
+    if !wants_ocr {
+        return Ok(());
+    }
+
+    for attachment in msg.attachments.iter().filter(|a| is_ocr_candidate(a)) {
+        let image = match attachment.download().await {
+            Ok(image) => image,
+            Err(err) => {
+                tracing::warn!(
+                    guild_id = guild_id.get(),
+                    attachment_id = attachment.id.get(),
+                    error = %err,
+                    "Failed to download attachment"
+                );
+                continue;
+            }
+        };
+        let text = match ocr::recognise(&image).await {
+            Ok(text) => text,
+            Err(err) => {
+                tracing::warn!(
+                    guild_id = guild_id.get(),
+                    attachment_id = attachment.id.get(),
+                    error = %err,
+                    "Failed to OCR attachment"
+                );
+                continue;
+            }
+        };
+
+        let stats = ocr::text_stats(&text);
+        if let Err(err) = data
+            .database
+            .insert_ocr_analytics(guild_id, msg.channel_id, &stats)
+            .await
+        {
+            tracing::error!(guild_id = guild_id.get(), error = %err, "Failed to record OCR analytics");
+        }
+
+        if let Some(trigger) = find_trigger(guild_settings, TriggerContext::OCR, &ancestry, &text) {
+            return respond(ctx, msg, trigger).await;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_ocr_candidate(attachment: &Attachment) -> bool {
+    attachment.size <= ocr::MAX_IMAGE_SIZE
+        && attachment
+            .content_type
+            .as_ref()
+            .is_some_and(|content_type| content_type.starts_with("image/"))
+}
+
+async fn respond(ctx: &Context, msg: &Message, trigger: &RegexTrigger) -> Result<(), Error> {
+    let Some(response) = trigger.trigger_metadata.text.as_deref() else {
+        return Ok(());
+    };
+
+    msg.channel_id
+        .send_message(
+            &ctx.http,
+            CreateMessage::new()
+                .content(response)
+                // the message may have been deleted since, the response still goes out.
+                .reference_message(MessageReference::from(msg).fail_if_not_exists(false))
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    Ok(())
+}
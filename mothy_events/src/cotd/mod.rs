@@ -0,0 +1,23 @@
+use std::{sync::Arc, time::Duration};
+
+use mothy_core::structs::Data;
+use serenity::all::Context;
+
+/// How often the schedule is checked, rotation times only have minute precision in practice.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Starts the colour of the day scheduler, this should only be called once.
+pub fn spawn_scheduler(ctx: Context, data: Arc<Data>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = mothy_core::cotd::rotate_due_roles(&ctx, &data.database).await {
+                tracing::error!(error = %e, "Failed to check colour of the day schedule");
+            }
+        }
+    });
+}
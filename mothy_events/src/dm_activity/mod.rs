@@ -0,0 +1,101 @@
+use std::{sync::Arc, time::Duration};
+
+use mothy_core::{NEGATIVE_COLOR_HEX, database_models::GuildFeatures, error::Error, structs::Data};
+use serenity::all::{
+    Context, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage,
+    GuildMemberUpdateEvent, Timestamp,
+};
+
+/// How often records past their guild's retention are deleted.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Starts the task purging old DM activity records, this should only be called once.
+pub fn spawn_purger(data: Arc<Data>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = data.database.purge_dm_activity().await {
+                tracing::error!(error = %e, "Failed to purge DM activity");
+            }
+        }
+    });
+}
+
+pub async fn guild_member_update(
+    ctx: &Context,
+    event: &GuildMemberUpdateEvent,
+    data: Arc<Data>,
+) -> Result<(), Error> {
+    // the flag is cleared by Discord once it expires, only active flags are interesting.
+    let Some(until) = event.unusual_dm_activity_until else {
+        return Ok(());
+    };
+    if until <= Timestamp::now() || event.user.bot() {
+        return Ok(());
+    }
+
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(event.guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if !guild_settings.features.contains(GuildFeatures::DM_ACTIVITY) {
+        return Ok(());
+    }
+
+    let record = data
+        .database
+        .record_dm_activity(event.guild_id, event.user.id, until)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    // other member updates arrive while the flag is still set, only announce new flags.
+    if !record.new_period {
+        return Ok(());
+    }
+
+    let settings = &guild_settings.dm_activity_settings;
+    let Some(announce_channel) = settings.announce_channel_id else {
+        return Ok(());
+    };
+
+    let cooldown = i64::from(settings.cooldown_seconds);
+    let now = Timestamp::now().unix_timestamp();
+    if record
+        .last_announced
+        .is_some_and(|last| now - last.unix_timestamp() < cooldown)
+    {
+        return Ok(());
+    }
+
+    let embed = CreateEmbed::new()
+        .author(
+            CreateEmbedAuthor::new(&event.user.name)
+                .icon_url(event.user.avatar_url().unwrap_or_default()),
+        )
+        .colour(NEGATIVE_COLOR_HEX)
+        .title("Unusual DM Activity")
+        .description(format!(
+            "<@{}> {} was flagged by Discord for unusual DM activity.",
+            event.user.id, event.user.name
+        ))
+        .field("Until", format!("<t:{}:f>", until.unix_timestamp()), true)
+        .field("Times Flagged", record.count.to_string(), true)
+        .timestamp(Timestamp::now())
+        .footer(CreateEmbedFooter::new(format!("ID: {}", event.user.id)));
+
+    announce_channel
+        .send_message(&ctx.http, CreateMessage::new().embed(embed))
+        .await?;
+
+    data.database
+        .mark_dm_activity_announced(event.guild_id, event.user.id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use std::{
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use mothy_core::{
+    database::{EmoteUsage, StickerUsage},
+    database_models::{EmoteUsageType, GuildFeatures},
+    error::Error,
+    structs::Data,
+};
+use regex::Regex;
+use serenity::all::{Context, EmojiId, Message, Reaction, ReactionType, Timestamp};
+
+/// How often queued usage is written to the database.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+static CUSTOM_EMOTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<a?:(\w{2,32}):(\d{17,20})>").unwrap());
+
+/// Starts the task writing queued expression usage, this should only be called once.
+pub fn spawn_flusher(data: Arc<Data>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = data.database.flush_expression_usage().await {
+                tracing::error!(error = %e, "Failed to write expression usage");
+            }
+        }
+    });
+}
+
+/// Queues the custom emotes and stickers in a message, the caller checks the feature flag.
+pub fn track_message(data: &Data, msg: &Message) {
+    let Some(guild_id) = msg.guild_id else {
+        return;
+    };
+    if msg.author.bot() {
+        return;
+    }
+
+    for (emote_id, emote_name) in parse_custom_emotes(&msg.content) {
+        data.database.queue_emote_usage(EmoteUsage {
+            message_id: msg.id,
+            guild_id,
+            channel_id: msg.channel_id,
+            user_id: msg.author.id,
+            emote_id,
+            emote_name: Some(emote_name),
+            usage_type: EmoteUsageType::Message,
+            used_at: msg.timestamp,
+        });
+    }
+
+    for sticker in &msg.sticker_items {
+        data.database.queue_sticker_usage(StickerUsage {
+            message_id: msg.id,
+            guild_id,
+            channel_id: msg.channel_id,
+            user_id: msg.author.id,
+            sticker_id: sticker.id,
+            sticker_name: sticker.name.to_string(),
+            used_at: msg.timestamp,
+        });
+    }
+}
+
+pub async fn reaction_add(reaction: &Reaction, data: Arc<Data>) -> Result<(), Error> {
+    let (Some(guild_id), Some(user_id)) = (reaction.guild_id, reaction.user_id) else {
+        return Ok(());
+    };
+    let ReactionType::Custom { id, name, .. } = &reaction.emoji else {
+        return Ok(());
+    };
+    if reaction.member.as_ref().is_some_and(|m| m.user.bot()) {
+        return Ok(());
+    }
+
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if !guild_settings
+        .features
+        .contains(GuildFeatures::EXPRESSION_TRACKING)
+    {
+        return Ok(());
+    }
+
+    data.database.queue_emote_usage(EmoteUsage {
+        message_id: reaction.message_id,
+        guild_id,
+        channel_id: reaction.channel_id,
+        user_id,
+        emote_id: *id,
+        // reactions from emotes the client can't see come through without a name.
+        emote_name: name.as_deref().map(ToString::to_string),
+        usage_type: EmoteUsageType::Reaction,
+        used_at: Timestamp::now(),
+    });
+
+    Ok(())
+}
+
+pub async fn reaction_remove(reaction: &Reaction, data: Arc<Data>) -> Result<(), Error> {
+    let (Some(guild_id), Some(user_id)) = (reaction.guild_id, reaction.user_id) else {
+        return Ok(());
+    };
+    let ReactionType::Custom { id, .. } = &reaction.emoji else {
+        return Ok(());
+    };
+
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if !guild_settings
+        .features
+        .contains(GuildFeatures::EXPRESSION_TRACKING)
+    {
+        return Ok(());
+    }
+
+    data.database
+        .remove_reaction_usage(reaction.message_id, user_id, *id)
+        .await
+        .map_err(Error::from_anyhow)
+}
+
+/// Finds each distinct custom emote in a message, in order of first use.
+fn parse_custom_emotes(content: &str) -> Vec<(EmojiId, String)> {
+    let mut emotes: Vec<(EmojiId, String)> = vec![];
+
+    for captures in CUSTOM_EMOTE.captures_iter(content) {
+        let Ok(id) = captures[2].parse::<u64>() else {
+            continue;
+        };
+        let id = EmojiId::new(id);
+
+        if !emotes.iter().any(|(existing, _)| *existing == id) {
+            emotes.push((id, captures[1].to_string()));
+        }
+    }
+
+    emotes
+}
+
+#[test]
+fn test_parse_custom_emotes() {
+    let emotes = parse_custom_emotes(
+        "hi <:moth:123456789012345678> <a:flap:223456789012345678> <:moth:123456789012345678> :plain:",
+    );
+    assert_eq!(
+        emotes,
+        vec![
+            (EmojiId::new(123456789012345678), "moth".to_string()),
+            (EmojiId::new(223456789012345678), "flap".to_string()),
+        ]
+    );
+    assert!(parse_custom_emotes("<:x:123456789012345678>").is_empty());
+}
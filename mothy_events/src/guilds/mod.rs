@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use mothy_core::{error::Error, structs::Data};
+use serenity::all::{Context, Guild};
+
+/// Leaves a guild that was banned from the bot, noting if it invited the bot back.
+pub async fn guild_create(
+    ctx: &Context,
+    guild: &Guild,
+    is_new: Option<bool>,
+    data: Arc<Data>,
+) -> Result<(), Error> {
+    let guild_handler = &data.database.guild_handler;
+    let guild_settings = guild_handler
+        .get(guild.id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if !guild_settings.banned() {
+        return Ok(());
+    }
+
+    // guilds are also created on startup, only a fresh join means someone re-invited the bot.
+    if is_new.unwrap_or(false) {
+        guild_handler
+            .set_rejoined(guild.id)
+            .await
+            .map_err(Error::from_anyhow)?;
+    }
+
+    tracing::info!(guild_id = guild.id.get(), guild = %guild.name, "Leaving banned guild");
+    ctx.http.leave_guild(guild.id).await?;
+
+    Ok(())
+}
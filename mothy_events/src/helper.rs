@@ -5,7 +5,8 @@ use std::sync::Arc;
 
 use ::serenity::all::GenericChannelId;
 // use mothy_ansi::{HI_GREEN, RED, RESET};
-use serenity::all::{Context, GuildId};
+use mothy_core::autoresponse::channel_ancestry;
+use serenity::all::{Context, GuildId, UserId};
 // use serenity::all::{
 // AutoArchiveDuration, ChannelType, Context, ForumLayoutType, GuildId, PermissionOverwrite,
 // PermissionOverwriteType, Permissions, SortOrder, User, UserId,
@@ -31,21 +32,41 @@ use serenity::all::{Context, GuildId};
 // }
 
 // Helper function for getting the guild name override or guild name even if None.
-pub fn get_guild_name_override(ctx: &Context, _: &Arc<Data>, guild_id: Option<GuildId>) -> String {
-    if guild_id.is_none() {
+pub fn get_guild_name_override(
+    ctx: &Context,
+    data: &Arc<Data>,
+    guild_id: Option<GuildId>,
+) -> String {
+    let Some(id) = guild_id else {
         return get_guild_name(ctx, guild_id);
+    };
+
+    if let Some(name) = data.config.read().unwrap().events.guild_name_override(id) {
+        return name.to_string();
     }
 
     get_guild_name(ctx, guild_id)
+}
 
-    // if let Some(overrides) = &data.config.read().events.guild_name_override {
-    //     overrides
-    //         .get(&guild_id.unwrap())
-    //         .unwrap_or(&get_guild_name(ctx, guild_id))
-    //         .to_string()
-    // } else {
-    //     get_guild_name(ctx, guild_id)
-    // }
+/// Whether activity by a user in a channel should be printed, see `Events::should_log`.
+pub fn should_log(
+    ctx: &Context,
+    data: &Data,
+    guild_id: Option<GuildId>,
+    channel_id: Option<GenericChannelId>,
+    user_id: Option<UserId>,
+) -> bool {
+    let channels = match (guild_id, channel_id) {
+        (Some(guild_id), Some(channel_id)) => channel_ancestry(&ctx.cache, guild_id, channel_id),
+        (None, Some(channel_id)) => vec![channel_id],
+        (_, None) => vec![],
+    };
+
+    data.config
+        .read()
+        .unwrap()
+        .events
+        .should_log(&channels, user_id)
 }
 
 // Helper function for getting the guild name even if ID is a None variant.
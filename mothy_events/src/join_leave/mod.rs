@@ -1,14 +1,18 @@
 use std::sync::Arc;
 
 use chrono::{DateTime, Datelike, Timelike, Utc};
-use mothy_ansi::{RESET, YELLOW};
-use mothy_core::{NEGATIVE_COLOR_HEX, POSITIVE_COLOR_HEX, error::Error, structs::Data};
+use mothy_core::{
+    NEGATIVE_COLOR_HEX, POSITIVE_COLOR_HEX,
+    database_models::{GuildFeatures, GuildSettings, StickyRoleSettings},
+    error::Error,
+    structs::Data,
+};
 use serenity::all::{
     Context, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage, GuildId, Member,
-    Timestamp, User,
+    RoleId, Timestamp, User,
 };
 
-use crate::helper::get_guild_name_override;
+use crate::helper::{get_guild_name_override, should_log};
 
 pub async fn guild_member_addition(
     ctx: &Context,
@@ -18,13 +22,17 @@ pub async fn guild_member_addition(
     let guild_id = new_member.guild_id;
     let joined_user_id = new_member.user.id;
 
-    if let Some(join_logs_channel) = data
-        .config
-        .logs
-        .mothy_join_logs_channel
-        .get(&new_member.guild_id)
-    {
-        let embed = CreateEmbed::new()
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    let restored_roles = restore_sticky_roles(ctx, &data, &guild_settings, new_member).await?;
+
+    if let Some(join_logs_channel) = guild_settings.log_settings.join_logs_channel_id {
+        let mut embed = CreateEmbed::new()
             .author(
                 CreateEmbedAuthor::new(&new_member.user.name)
                     .icon_url(new_member.avatar_url().unwrap_or_default()),
@@ -45,19 +53,34 @@ pub async fn guild_member_addition(
                 "ID: {}",
                 new_member.user.id
             )));
+        if !restored_roles.is_empty() {
+            embed = embed.field(
+                "Sticky Roles Restored",
+                restored_roles
+                    .iter()
+                    .map(|id| format!("<@&{id}>"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                false,
+            );
+        }
         let _ = join_logs_channel
             .send_message(&ctx.http, CreateMessage::new().embed(embed))
             .await;
     }
 
-    let guild_name = get_guild_name_override(ctx, &data, Some(guild_id));
+    if should_log(ctx, &data, Some(guild_id), None, Some(joined_user_id)) {
+        let guild_name = get_guild_name_override(ctx, &data, Some(guild_id));
 
-    println!(
-        "{YELLOW}[{}] {} (ID:{}) has joined!{RESET}",
-        guild_name,
-        new_member.user.tag(),
-        joined_user_id
-    );
+        tracing::info!(
+            event = "member_join",
+            guild_id = guild_id.get(),
+            user_id = joined_user_id.get(),
+            guild = %guild_name,
+            author = %new_member.user.tag(),
+            "has joined!"
+        );
+    }
 
     Ok(())
 }
@@ -66,18 +89,34 @@ pub async fn guild_member_removal(
     ctx: &Context,
     guild_id: &GuildId,
     user: &User,
+    member: Option<&Member>,
     data: Arc<Data>,
 ) -> Result<(), Error> {
-    let guild_name = get_guild_name_override(ctx, &data, Some(*guild_id));
+    if should_log(ctx, &data, Some(*guild_id), None, Some(user.id)) {
+        let guild_name = get_guild_name_override(ctx, &data, Some(*guild_id));
+
+        tracing::info!(
+            event = "member_leave",
+            guild_id = guild_id.get(),
+            user_id = user.id.get(),
+            guild = %guild_name,
+            author = %user.tag(),
+            "has left!"
+        );
+    }
 
-    println!(
-        "{YELLOW}[{}] {} (ID:{}) has left!{RESET}",
-        guild_name,
-        user.tag(),
-        user.id
-    );
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(*guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
 
-    if let Some(join_logs_channel) = data.config.logs.mothy_join_logs_channel.get(guild_id) {
+    if let Some(member) = member {
+        save_sticky_roles(ctx, &data, &guild_settings, member).await?;
+    }
+
+    if let Some(join_logs_channel) = guild_settings.log_settings.join_logs_channel_id {
         let embed = CreateEmbed::new()
             .author(
                 CreateEmbedAuthor::new(&user.name).icon_url(user.avatar_url().unwrap_or_default()),
@@ -95,6 +134,131 @@ pub async fn guild_member_removal(
     Ok(())
 }
 
+async fn save_sticky_roles(
+    ctx: &Context,
+    data: &Data,
+    guild_settings: &GuildSettings,
+    member: &Member,
+) -> Result<(), Error> {
+    let sticky_settings = &guild_settings.sticky_role_settings;
+    if !guild_settings
+        .features
+        .contains(GuildFeatures::STICKY_ROLES)
+        || !sticky_settings.is_enabled
+    {
+        return Ok(());
+    }
+
+    // an older snapshot has to go when nothing sticks now, or roles taken away since would come
+    // back on the next join.
+    let roles = filter_sticky_roles(ctx, member.guild_id, sticky_settings, &member.roles)
+        .unwrap_or_default();
+    let result = if roles.is_empty() {
+        data.database
+            .delete_sticky_roles(member.guild_id, member.user.id)
+            .await
+    } else {
+        data.database
+            .save_sticky_roles(member.guild_id, member.user.id, &roles)
+            .await
+    };
+    result.map_err(Error::from_anyhow)
+}
+
+async fn restore_sticky_roles(
+    ctx: &Context,
+    data: &Data,
+    guild_settings: &GuildSettings,
+    member: &Member,
+) -> Result<Vec<RoleId>, Error> {
+    let sticky_settings = &guild_settings.sticky_role_settings;
+    if !guild_settings
+        .features
+        .contains(GuildFeatures::STICKY_ROLES)
+        || !sticky_settings.is_enabled
+    {
+        // roles saved before the feature was turned off would otherwise come back once it's on.
+        data.database
+            .delete_sticky_roles(member.guild_id, member.user.id)
+            .await
+            .map_err(Error::from_anyhow)?;
+        return Ok(vec![]);
+    }
+
+    let saved_roles = data
+        .database
+        .get_sticky_roles(member.guild_id, member.user.id)
+        .await
+        .map_err(Error::from_anyhow)?;
+    if saved_roles.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // the settings may have changed since the roles were saved, so filter them again. The roles
+    // are kept for the next join if the guild can't be checked.
+    let Some(roles) = filter_sticky_roles(ctx, member.guild_id, sticky_settings, &saved_roles)
+    else {
+        return Ok(vec![]);
+    };
+
+    let mut restored_roles = vec![];
+    let mut failed_roles = vec![];
+    for role_id in roles {
+        // a role above the bot's highest role fails, that shouldn't stop the rest from being given back.
+        if ctx
+            .http
+            .add_member_role(
+                member.guild_id,
+                member.user.id,
+                role_id,
+                Some("Restoring sticky roles"),
+            )
+            .await
+            .is_ok()
+        {
+            restored_roles.push(role_id);
+        } else {
+            failed_roles.push(role_id);
+        }
+    }
+
+    // only the roles that couldn't be given back are kept.
+    let result = if failed_roles.is_empty() {
+        data.database
+            .delete_sticky_roles(member.guild_id, member.user.id)
+            .await
+    } else {
+        data.database
+            .save_sticky_roles(member.guild_id, member.user.id, &failed_roles)
+            .await
+    };
+    result.map_err(Error::from_anyhow)?;
+
+    Ok(restored_roles)
+}
+
+/// Keeps the roles that should stick, skipping deleted roles and managed roles as those can't be
+/// assigned. `None` if the guild isn't cached, so the roles can't be checked.
+fn filter_sticky_roles(
+    ctx: &Context,
+    guild_id: GuildId,
+    sticky_settings: &StickyRoleSettings,
+    roles: &[RoleId],
+) -> Option<Vec<RoleId>> {
+    let guild = ctx.cache.guild(guild_id)?;
+
+    Some(
+        roles
+            .iter()
+            .copied()
+            .filter(|role_id| {
+                sticky_settings.is_sticky(*role_id)
+                    && guild.roles.get(role_id).is_some_and(|role| !role.managed())
+            })
+            .collect(),
+    )
+}
+
 fn get_member_joined_at(new_member: &Member) -> Option<String> {
     let user_account_creation_date = &new_member.user.id.created_at();
 
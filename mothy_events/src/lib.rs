@@ -1,6 +1,12 @@
 pub use mothy_core::{error::Error, structs::Data};
 use serenity::all::{self as serenity, FullEvent};
 
+mod automod;
+mod autoresponse;
+mod cotd;
+mod dm_activity;
+mod expressions;
+mod guilds;
 mod helper;
 mod join_leave;
 mod messages;
@@ -12,7 +18,7 @@ pub struct Handler;
 impl serenity::EventHandler for Handler {
     async fn dispatch(&self, ctx: &serenity::Context, event: &FullEvent) {
         if let Err(e) = event_handler(ctx, event).await {
-            mothy_core::error::event_handler(ctx, e).await;
+            mothy_core::error::event_handler(ctx, event, e).await;
         }
     }
 }
@@ -31,7 +37,14 @@ pub async fn event_handler(ctx: &serenity::Context, event: &FullEvent) -> Result
                     .has_started
                     .swap(true, std::sync::atomic::Ordering::SeqCst)
             {
-                println!("Logged in as {}", data_about_bot.user.tag());
+                tracing::info!(
+                    user_id = data_about_bot.user.id.get(),
+                    "Logged in as {}",
+                    data_about_bot.user.tag()
+                );
+                cotd::spawn_scheduler(ctx.clone(), ctx.data::<Data>());
+                expressions::spawn_flusher(ctx.data::<Data>());
+                dm_activity::spawn_purger(ctx.data::<Data>());
             }
         }
         FullEvent::Message { new_message, .. } => {
@@ -46,11 +59,40 @@ pub async fn event_handler(ctx: &serenity::Context, event: &FullEvent) -> Result
             messages::on_message_delete(ctx, *channel_id, *deleted_message_id, *guild_id, data)
                 .await?;
         }
+        FullEvent::ReactionAdd { add_reaction, .. } => {
+            expressions::reaction_add(add_reaction, data).await?;
+        }
+        FullEvent::ReactionRemove {
+            removed_reaction, ..
+        } => {
+            expressions::reaction_remove(removed_reaction, data).await?;
+        }
+        FullEvent::GuildCreate { guild, is_new, .. } => {
+            guilds::guild_create(ctx, guild, *is_new, data).await?;
+        }
         FullEvent::GuildMemberAddition { new_member, .. } => {
             join_leave::guild_member_addition(ctx, new_member, data).await?;
         }
-        FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
-            join_leave::guild_member_removal(ctx, guild_id, user, data).await?;
+        FullEvent::GuildMemberRemoval {
+            guild_id,
+            user,
+            member_data_if_available,
+            ..
+        } => {
+            join_leave::guild_member_removal(
+                ctx,
+                guild_id,
+                user,
+                member_data_if_available.as_ref(),
+                data,
+            )
+            .await?;
+        }
+        FullEvent::GuildMemberUpdate { event, .. } => {
+            dm_activity::guild_member_update(ctx, event, data).await?;
+        }
+        FullEvent::AutoModActionExecution { execution, .. } => {
+            automod::action_execution(ctx, execution, &data).await?;
         }
         FullEvent::VoiceStateUpdate { old, new, .. } => {
             voice::voice_state_update(ctx, &data, old, new).await?;
@@ -0,0 +1,431 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use mothy_core::NEGATIVE_COLOR_HEX;
+use serenity::all::{
+    Context, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, GenericChannelId, GuildId, Message,
+    MessageId, Timestamp, UserId,
+};
+
+use super::{FilterLog, FilterVerdict};
+
+/// How long a message is remembered for.
+const WINDOW: Duration = Duration::from_secs(20);
+/// The same message in this many channels within the window is spam.
+const CHANNEL_TRIGGER: usize = 3;
+/// So is the same message this many times, wherever it was sent, if it's countable.
+const COUNT_TRIGGER: usize = 5;
+/// Shorter messages without links or attachments aren't countable, "lol" is repeated in ordinary
+/// chat all the time.
+const MIN_COUNTABLE_LENGTH: usize = 20;
+/// Everyone's history is pruned at once when this many users are tracked.
+const MAX_TRACKED_USERS: usize = 10_000;
+
+static TRACKER: LazyLock<Mutex<DuplicateTracker>> =
+    LazyLock::new(|| Mutex::new(DuplicateTracker::default()));
+
+struct SeenMessage {
+    fingerprint: u64,
+    channel_id: GenericChannelId,
+    message_id: MessageId,
+    seen_at: Instant,
+    /// Already handed out for deletion, still counted so later duplicates trigger straight away.
+    deleted: bool,
+}
+
+/// Recent messages per guild and user, to find the same message posted over and over.
+#[derive(Default)]
+struct DuplicateTracker {
+    seen: HashMap<(GuildId, UserId), VecDeque<SeenMessage>>,
+}
+
+impl DuplicateTracker {
+    /// Records a message, returning every duplicate of it that hasn't been deleted yet, itself
+    /// included, once there are too many within the window. How many times it was sent only
+    /// counts if it's `countable`, see [`is_countable`].
+    fn record(
+        &mut self,
+        key: (GuildId, UserId),
+        fingerprint: u64,
+        channel_id: GenericChannelId,
+        message_id: MessageId,
+        now: Instant,
+        countable: bool,
+    ) -> Option<Vec<(GenericChannelId, MessageId)>> {
+        if self.seen.len() >= MAX_TRACKED_USERS {
+            self.prune(now);
+        }
+
+        let seen = self.seen.entry(key).or_default();
+        while seen
+            .front()
+            .is_some_and(|message| now.duration_since(message.seen_at) >= WINDOW)
+        {
+            seen.pop_front();
+        }
+        seen.push_back(SeenMessage {
+            fingerprint,
+            channel_id,
+            message_id,
+            seen_at: now,
+            deleted: false,
+        });
+
+        let mut channels = Vec::new();
+        let mut count = 0;
+        for message in seen.iter().filter(|m| m.fingerprint == fingerprint) {
+            count += 1;
+            if !channels.contains(&message.channel_id) {
+                channels.push(message.channel_id);
+            }
+        }
+        if channels.len() < CHANNEL_TRIGGER && (count < COUNT_TRIGGER || !countable) {
+            return None;
+        }
+
+        let duplicates = seen
+            .iter_mut()
+            .filter(|m| m.fingerprint == fingerprint && !m.deleted)
+            .map(|m| {
+                m.deleted = true;
+                (m.channel_id, m.message_id)
+            })
+            .collect();
+        Some(duplicates)
+    }
+
+    /// Remembers whether messages were deleted. Deleted ones aren't handed out for deletion
+    /// again, ones that couldn't be deleted after all are handed out on the next trigger.
+    fn set_deleted(&mut self, key: (GuildId, UserId), message_ids: &[MessageId], deleted: bool) {
+        let Some(seen) = self.seen.get_mut(&key) else {
+            return;
+        };
+        for message in seen
+            .iter_mut()
+            .filter(|m| message_ids.contains(&m.message_id))
+        {
+            message.deleted = deleted;
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        self.seen.retain(|_, seen| {
+            seen.retain(|message| now.duration_since(message.seen_at) < WINDOW);
+            !seen.is_empty()
+        });
+    }
+}
+
+/// Identifies a message by its text, ignoring case and spacing, and its attachments.
+///
+/// Discord doesn't hash attachments and downloading every one is too slow, so attachments are
+/// told apart by name and size, which is identical when the same files are reposted.
+fn fingerprint<'a>(content: &str, attachments: impl Iterator<Item = (&'a str, u32)>) -> u64 {
+    let mut attachments: Vec<_> = attachments.collect();
+    attachments.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    for word in content.split_whitespace() {
+        word.to_lowercase().hash(&mut hasher);
+    }
+    attachments.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether repeats of a message count towards [`COUNT_TRIGGER`]: it has a link or attachments,
+/// or is long enough not to be ordinary chat.
+fn is_countable(content: &str, has_attachments: bool) -> bool {
+    has_attachments
+        || content.contains("://")
+        || content.trim().chars().count() >= MIN_COUNTABLE_LENGTH
+}
+
+/// Flags a message posted across channels or over and over in a short time, along with every
+/// copy of it. Every message is tracked, `already_filtered` ones are remembered as deleted.
+pub(super) fn duplicate_spam_filter<'a>(
+    ctx: &Context,
+    msg: &'a Message,
+    already_filtered: bool,
+) -> Option<FilterVerdict<'a>> {
+    let guild_id = msg.guild_id?;
+    if msg.content.trim().is_empty() && msg.attachments.is_empty() {
+        return None;
+    }
+
+    let fingerprint = fingerprint(
+        &msg.content,
+        msg.attachments
+            .iter()
+            .map(|attachment| (attachment.filename.as_str(), attachment.size)),
+    );
+    let key = (guild_id, msg.author.id);
+    let duplicates = {
+        let mut tracker = TRACKER.lock().unwrap();
+        let duplicates = tracker.record(
+            key,
+            fingerprint,
+            msg.channel_id,
+            msg.id,
+            Instant::now(),
+            is_countable(&msg.content, !msg.attachments.is_empty()),
+        );
+        if already_filtered {
+            tracker.set_deleted(key, &[msg.id], true);
+        }
+        duplicates
+    }?;
+
+    let mut by_channel: Vec<(GenericChannelId, usize)> = Vec::new();
+    for (channel_id, _) in &duplicates {
+        match by_channel.iter_mut().find(|(id, _)| id == channel_id) {
+            Some((_, count)) => *count += 1,
+            None => by_channel.push((*channel_id, 1)),
+        }
+    }
+
+    let message_content_format = if msg.content.is_empty() {
+        "(No message content)".to_string()
+    } else {
+        format!(
+            "```\n{}\n```",
+            &msg.content_safe(&ctx.cache).replace("`", "\\`")
+        )
+    };
+    // a channel mention and count is under 32 characters, so this stays within an embed field.
+    let mut channels = by_channel
+        .iter()
+        .take(30)
+        .map(|(channel_id, count)| format!("<#{channel_id}> ({count})"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if by_channel.len() > 30 {
+        channels.push_str("\n…");
+    }
+
+    let embed = CreateEmbed::new()
+        .author(
+            CreateEmbedAuthor::new(&msg.author.name)
+                .icon_url(msg.author.avatar_url().unwrap_or_default()),
+        )
+        .colour(NEGATIVE_COLOR_HEX)
+        .title("Message Filtered")
+        .description(format!(
+            "{} messages sent by <@{}> deleted\n{}",
+            duplicates.len(),
+            msg.author.id,
+            message_content_format
+        ))
+        .field("Deleted From", channels, false)
+        .field("Reason", "Duplicate Spam Detected", true)
+        .field(
+            "Rule",
+            format!(
+                "Users without filter bypass roles or moderator permissions must not send the \
+                 same message in {CHANNEL_TRIGGER} channels, or the same link, attachment or \
+                 longer message {COUNT_TRIGGER} times, within {} seconds",
+                WINDOW.as_secs()
+            ),
+            true,
+        )
+        .timestamp(Timestamp::now())
+        .footer(CreateEmbedFooter::new(format!("ID: {}", msg.author.id)));
+
+    Some(FilterVerdict {
+        reason: "duplicate spam",
+        rules: Vec::new(),
+        copies: duplicates
+            .into_iter()
+            .filter(|(_, message_id)| *message_id != msg.id)
+            .collect(),
+        embed,
+    })
+}
+
+/// Hands messages that couldn't be deleted out again the next time a copy of them is spam.
+pub(super) fn deletion_failed(msg: &Message, message_ids: &[MessageId]) {
+    if let Some(guild_id) = msg.guild_id {
+        TRACKER
+            .lock()
+            .unwrap()
+            .set_deleted((guild_id, msg.author.id), message_ids, false);
+    }
+}
+
+/// Deletes the earlier copies of a filtered message, in one request per channel.
+pub(super) async fn delete_copies(
+    ctx: &Context,
+    msg: &Message,
+    log: &FilterLog,
+    copies: Vec<(GenericChannelId, MessageId)>,
+) {
+    let mut by_channel: Vec<(GenericChannelId, Vec<MessageId>)> = Vec::new();
+    for (channel_id, message_id) in copies {
+        match by_channel.iter_mut().find(|(id, _)| *id == channel_id) {
+            Some((_, message_ids)) => message_ids.push(message_id),
+            None => by_channel.push((channel_id, vec![message_id])),
+        }
+    }
+
+    for (channel_id, message_ids) in &by_channel {
+        let result = match message_ids.as_slice() {
+            [message_id] => {
+                channel_id
+                    .delete_message(&ctx.http, *message_id, None)
+                    .await
+            }
+            message_ids => {
+                channel_id
+                    .delete_messages(&ctx.http, message_ids, None)
+                    .await
+            }
+        };
+        if let Err(err) = result {
+            deletion_failed(msg, message_ids);
+            tracing::warn!(
+                event = "message_filter_failed",
+                guild_id = msg.guild_id.map(GuildId::get),
+                channel_id = channel_id.get(),
+                user_id = msg.author.id.get(),
+                message_id = msg.id.get(),
+                guild = %log.guild_name,
+                channel = %log.channel_name,
+                author = %msg.author.tag(),
+                author_colour = log.author_colour,
+                duplicates = message_ids.len(),
+                error = %err,
+                "{}",
+                msg.content
+            );
+        }
+    }
+}
+
+#[test]
+fn test_duplicate_tracker() {
+    let mut tracker = DuplicateTracker::default();
+    let start = Instant::now();
+    let key = (GuildId::new(1), UserId::new(1));
+    let other_key = (GuildId::new(1), UserId::new(2));
+    let channel = GenericChannelId::new;
+    let message = MessageId::new;
+
+    let spam = fingerprint("Free  NITRO https://evil.ru", std::iter::empty());
+    assert_eq!(
+        spam,
+        fingerprint("free nitro https://evil.ru ", std::iter::empty())
+    );
+    assert_ne!(spam, fingerprint("free nitro", [("a.png", 10)].into_iter()));
+
+    assert!(
+        tracker
+            .record(key, spam, channel(1), message(1), start, true)
+            .is_none()
+    );
+    assert!(
+        tracker
+            .record(other_key, spam, channel(2), message(2), start, true)
+            .is_none()
+    );
+    assert!(
+        tracker
+            .record(key, spam + 1, channel(2), message(3), start, true)
+            .is_none()
+    );
+    assert!(
+        tracker
+            .record(key, spam, channel(2), message(4), start, true)
+            .is_none()
+    );
+    assert_eq!(
+        tracker.record(
+            key,
+            spam,
+            channel(3),
+            message(5),
+            start + Duration::from_secs(1),
+            true
+        ),
+        Some(vec![
+            (channel(1), message(1)),
+            (channel(2), message(4)),
+            (channel(3), message(5)),
+        ])
+    );
+    // only the new copy is left to delete.
+    assert_eq!(
+        tracker.record(
+            key,
+            spam,
+            channel(4),
+            message(6),
+            start + Duration::from_secs(2),
+            true
+        ),
+        Some(vec![(channel(4), message(6))])
+    );
+
+    // the earlier copies have left the window.
+    assert!(
+        tracker
+            .record(
+                key,
+                spam,
+                channel(1),
+                message(7),
+                start + WINDOW + Duration::from_secs(2),
+                true
+            )
+            .is_none()
+    );
+
+    let later = start + WINDOW * 3;
+    for id in 8..8 + COUNT_TRIGGER as u64 - 1 {
+        assert!(
+            tracker
+                .record(key, spam, channel(1), message(id), later, true)
+                .is_none()
+        );
+    }
+    assert_eq!(
+        tracker
+            .record(key, spam, channel(1), message(100), later, true)
+            .map(|duplicates| duplicates.len()),
+        Some(COUNT_TRIGGER)
+    );
+
+    // short chat is only spam when it's sent to several channels.
+    assert!(!is_countable(" lol ", false));
+    assert!(is_countable("lol https://evil.ru", false));
+    assert!(is_countable("lol", true));
+    let chat = fingerprint("lol", std::iter::empty());
+    for id in 200..200 + COUNT_TRIGGER as u64 * 2 {
+        assert!(
+            tracker
+                .record(other_key, chat, channel(1), message(id), later, false)
+                .is_none()
+        );
+    }
+    assert!(
+        tracker
+            .record(other_key, chat, channel(2), message(300), later, false)
+            .is_none()
+    );
+    // copies another filter already deleted aren't handed out again.
+    tracker.set_deleted(other_key, &[message(200)], true);
+    let duplicates = tracker
+        .record(other_key, chat, channel(3), message(301), later, false)
+        .unwrap();
+    assert_eq!(duplicates.len(), COUNT_TRIGGER * 2 + 1);
+    assert!(!duplicates.contains(&(channel(1), message(200))));
+    // ones that couldn't be deleted are handed out again.
+    tracker.set_deleted(other_key, &[message(300)], false);
+    assert_eq!(
+        tracker.record(other_key, chat, channel(4), message(302), later, false),
+        Some(vec![(channel(2), message(300)), (channel(4), message(302))])
+    );
+
+    tracker.prune(later + WINDOW);
+    assert!(tracker.seen.is_empty());
+}
@@ -1,15 +1,21 @@
-use mothy_ansi::{CYAN, DIM, HI_BLACK, HI_RED, RESET};
-use mothy_core::{NEGATIVE_COLOR_HEX, error::Error, structs::Data};
+use mothy_core::{
+    NEGATIVE_COLOR_HEX,
+    database_models::{GuildFeatures, GuildSettings, ModRolePermissions},
+    error::Error,
+    structs::{Data, Redirects},
+};
 use serenity::all::{
     Context, CreateAllowedMentions, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter,
     CreateMessage, GenericChannelId, GuildId, Message, MessageId, Role, Timestamp,
 };
-use std::{fmt::Write, sync::Arc};
+use std::sync::Arc;
+
+use crate::helper::{get_channel_name, get_guild_name_override, should_log};
 
-use crate::helper::{get_channel_name, get_guild_name_override};
+mod duplicates;
+mod punishments;
 
 pub async fn on_message(ctx: &Context, msg: &Message, data: Arc<Data>) -> Result<(), Error> {
-    let dont_print = false;
     let content = {
         // moth_filter::filter_content(&msg.content, &config.badlist, &config.fixlist);
 
@@ -17,140 +23,365 @@ pub async fn on_message(ctx: &Context, msg: &Message, data: Arc<Data>) -> Result
     };
 
     let guild_id = msg.guild_id;
+    let print = should_log(
+        ctx,
+        &data,
+        guild_id,
+        Some(msg.channel_id),
+        Some(msg.author.id),
+    );
     let guild_name = get_guild_name_override(ctx, &data, guild_id);
     let channel_name = get_channel_name(ctx, guild_id, msg.channel_id).await;
 
     let (attachments, embeds) = attachments_embed_fmt(msg);
 
-    let author_string = author_string(ctx, msg);
-
-    if !dont_print {
-        println!(
-            "{HI_BLACK}[{guild_name}] [#{channel_name}]{RESET} {author_string}: \
-             {content}{RESET}{CYAN}{}{}{RESET}",
-            attachments.as_deref().unwrap_or(""),
-            embeds.as_deref().unwrap_or("")
+    let author_colour = author_colour(ctx, msg);
+
+    if print {
+        tracing::info!(
+            event = "message",
+            guild_id = guild_id.map(GuildId::get),
+            channel_id = msg.channel_id.get(),
+            user_id = msg.author.id.get(),
+            message_id = msg.id.get(),
+            guild = %guild_name,
+            channel = %channel_name,
+            author = %msg.author.tag(),
+            author_colour,
+            attachments = attachments.as_deref(),
+            embeds = embeds.as_deref(),
+            "{content}"
         );
     }
 
+    let Some(guild_id) = guild_id else {
+        return Ok(());
+    };
+
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    if guild_settings
+        .features
+        .contains(GuildFeatures::EXPRESSION_TRACKING)
+    {
+        crate::expressions::track_message(&data, msg);
+    }
+
     let user_roles = if let Some(member) = msg.member.as_ref() {
         member.roles.to_vec()
     } else {
         vec![]
     };
 
-    let filters_valid_guild = data
-        .config
-        .filters
-        .filters_allowed_guilds
-        .contains(&guild_id.unwrap_or_default());
-    let filters_valid_author = !data
-        .config
-        .filters
-        .filter_bypass_roles
+    let filter_settings = &guild_settings.filter_settings;
+    let filters_valid_author = !filter_settings
+        .bypass_roles
         .iter()
         .any(|x| user_roles.contains(x));
     let permissions = msg.author_permissions(&ctx.cache).unwrap_or_default();
-    let admin_or_mod = permissions.moderate_members() || permissions.administrator();
-
-    if filters_valid_guild && filters_valid_author && !msg.author.bot() && !admin_or_mod {
-        let _ = tokio::join!(
-            image_spambot_filter(ctx, &data, msg, attachments),
-            regex_blacklist_filter(ctx, &data, msg, guild_name, channel_name, author_string),
-        );
+    let admin_or_mod =
+        ModRolePermissions::resolve(&guild_settings.mod_roles, &user_roles, permissions)
+            .contains(ModRolePermissions::BYPASS_FILTERS);
+
+    let mut filtered = false;
+    if guild_settings
+        .features
+        .contains(GuildFeatures::AUTOMODERATION)
+        && filter_settings.is_enabled
+        && filters_valid_author
+        && !msg.author.bot()
+        && !admin_or_mod
+    {
+        let filter_log = FilterLog {
+            guild_name,
+            channel_name,
+            author_colour,
+            print,
+        };
+        let mut verdicts: Vec<FilterVerdict<'_>> = [
+            image_spambot_filter(ctx, msg, attachments),
+            regex_blacklist_filter(ctx, &data, msg).await,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        // runs last so it knows whether the message is already being deleted.
+        verdicts.extend(duplicates::duplicate_spam_filter(
+            ctx,
+            msg,
+            !verdicts.is_empty(),
+        ));
+        if !verdicts.is_empty() {
+            filtered = true;
+            apply_filter_verdicts(ctx, &data, &guild_settings, msg, &filter_log, verdicts).await;
+        }
     }
 
-    let Some(_) = msg.guild_id else { return Ok(()) };
+    // filtered messages are deleted, or should have been, so they aren't responded to.
+    if !filtered
+        && guild_settings
+            .features
+            .contains(GuildFeatures::AUTORESPONSE)
+    {
+        crate::autoresponse::on_message(ctx, &data, guild_id, &guild_settings, msg).await?;
+    }
 
     Ok(())
 }
 
-async fn regex_blacklist_filter(
+/// How the filters log the message they acted on, the same way it was printed.
+struct FilterLog {
+    guild_name: String,
+    channel_name: String,
+    author_colour: u32,
+    /// Whether the message may be printed, see `should_log`.
+    print: bool,
+}
+
+/// What a filter found wrong with a message. Every filter has its say before anything is done,
+/// so a message tripping several of them is only deleted and punished once.
+struct FilterVerdict<'a> {
+    /// Why the message was filtered, for the logs and the audit log.
+    reason: &'static str,
+    /// The blacklist patterns the message matched, if any.
+    rules: Vec<String>,
+    /// Earlier copies of the message, deleted along with it.
+    copies: Vec<(GenericChannelId, MessageId)>,
+    /// The log channel embed, the action taken is added once it's known.
+    embed: CreateEmbed<'a>,
+}
+
+/// Deletes a message the filters flagged and punishes its author, once however many filters
+/// flagged it. Nothing is punished if the message couldn't be deleted.
+async fn apply_filter_verdicts(
     ctx: &Context,
     data: &Data,
+    guild_settings: &GuildSettings,
     msg: &Message,
-    guild_name: String,
-    channel_name: String,
-    author_string: String,
-) -> Result<(), Error> {
+    log: &FilterLog,
+    verdicts: Vec<FilterVerdict<'_>>,
+) {
+    let reasons: Vec<&str> = verdicts.iter().map(|verdict| verdict.reason).collect();
+    let rules: Vec<&str> = verdicts
+        .iter()
+        .flat_map(|verdict| verdict.rules.iter().map(String::as_str))
+        .collect();
+
+    if let Err(err) = msg.delete(&ctx.http, None).await {
+        tracing::warn!(
+            event = "message_filter_failed",
+            guild_id = msg.guild_id.map(GuildId::get),
+            channel_id = msg.channel_id.get(),
+            user_id = msg.author.id.get(),
+            message_id = msg.id.get(),
+            guild = %log.guild_name,
+            channel = %log.channel_name,
+            author = %msg.author.tag(),
+            author_colour = log.author_colour,
+            reasons = ?reasons,
+            rules = ?rules,
+            error = %err,
+            "{}",
+            msg.content
+        );
+        // the copies weren't deleted either, a later copy gets to try again.
+        let message_ids: Vec<MessageId> = verdicts
+            .iter()
+            .flat_map(|verdict| verdict.copies.iter().map(|(_, message_id)| *message_id))
+            .chain([msg.id])
+            .collect();
+        duplicates::deletion_failed(msg, &message_ids);
+        return;
+    }
+
+    if log.print {
+        tracing::info!(
+            event = "message_filtered",
+            guild_id = msg.guild_id.map(GuildId::get),
+            channel_id = msg.channel_id.get(),
+            user_id = msg.author.id.get(),
+            message_id = msg.id.get(),
+            guild = %log.guild_name,
+            channel = %log.channel_name,
+            author = %msg.author.tag(),
+            author_colour = log.author_colour,
+            reasons = ?reasons,
+            rules = ?rules,
+            "{}",
+            msg.content
+        );
+    }
+
+    let copies: Vec<(GenericChannelId, MessageId)> = verdicts
+        .iter()
+        .flat_map(|verdict| verdict.copies.iter().copied())
+        .collect();
+    if !copies.is_empty() {
+        duplicates::delete_copies(ctx, msg, log, copies).await;
+    }
+
+    let action = punishments::punish(ctx, data, guild_settings, msg, &reasons.join(", ")).await;
+
+    let Some(blacklist_logs_channel) = guild_settings.log_settings.blacklist_logs_channel_id else {
+        return;
+    };
+    let embeds: Vec<CreateEmbed<'_>> = verdicts
+        .into_iter()
+        .map(|verdict| match &action {
+            Some(action) => verdict.embed.field("Action", action.clone(), false),
+            None => verdict.embed,
+        })
+        .collect();
+    let mentions = CreateAllowedMentions::new()
+        .everyone(false)
+        .all_roles(false)
+        .all_users(false);
+    if let Err(e) = blacklist_logs_channel
+        .send_message(
+            &ctx.http,
+            CreateMessage::new()
+                .embeds(embeds)
+                .allowed_mentions(mentions),
+        )
+        .await
+    {
+        tracing::warn!(
+            guild_id = msg.guild_id.map(GuildId::get),
+            channel_id = blacklist_logs_channel.get(),
+            error = %e,
+            "Failed to log a filtered message"
+        );
+    }
+}
+
+/// Flags messages with links matching the blacklist, directly or through redirects.
+async fn regex_blacklist_filter<'a>(
+    ctx: &Context,
+    data: &Data,
+    msg: &'a Message,
+) -> Option<FilterVerdict<'a>> {
     let regex_filters = &data.regex_filters;
     let content = &msg.content;
 
-    let links = regex_filters
+    let links: Vec<&str> = regex_filters
         .links_detector
         .captures_iter(content)
-        .fold(vec![], |mut acc, x| {
-            if let Some(x_some) = x.get(0) {
-                acc.push(x_some.as_str());
-            };
-            acc
-        })
-        .join("\n");
-
-    for regex_filter in &regex_filters.links_blacklist {
-        if let Some(regex_match) = regex_filter.find(&links) {
-            match msg.delete(&ctx.http, None).await {
-                Ok(_) => {
-                    println!(
-                        "{HI_RED}REGEX DELETED [{guild_name}] [#{channel_name}]{RESET} {author_string}: \
-                        {content}{RESET}{CYAN}{RESET}"
-                    );
-                    if let Some(blacklist_logs_channel) = data
-                        .config
-                        .logs
-                        .mothy_blacklist_logs_channel
-                        .get(&msg.guild_id.unwrap_or_default())
-                    {
-                        let embed = CreateEmbed::new()
-                            .author(
-                                CreateEmbedAuthor::new(&msg.author.name)
-                                    .icon_url(msg.author.avatar_url().unwrap_or_default()),
-                            )
-                            .colour(NEGATIVE_COLOR_HEX)
-                            .title("Message Filtered")
-                            .description(format!(
-                                "Message sent by <@{}> deleted in <#{}>\n```\n{}\n```",
-                                msg.author.id,
-                                msg.channel_id,
-                                &msg.content_safe(&ctx.cache).replace("`", "\\`")
-                            ))
-                            .field(
-                                "Reason",
-                                format!("Bad Link: `{}`", regex_match.as_str().replace("`", "\\`")),
-                                true,
-                            )
-                            .field(
-                                "Rule",
-                                format!("`{}`", regex_filter.as_str().replace("`", "\\`")),
-                                true,
-                            )
-                            .timestamp(Timestamp::now())
-                            .footer(CreateEmbedFooter::new(format!("ID: {}", msg.author.id)));
-                        blacklist_logs_channel
-                            .send_message(&ctx.http, CreateMessage::new().embed(embed))
-                            .await?;
-                    }
-                }
-                Err(err) => {
-                    println!(
-                        "FAILED TO REGEX DELETE {HI_RED}[{guild_name}] [#{channel_name}]{RESET} {author_string}: \
-                        {content}{RESET}{CYAN}{RESET}"
-                    );
-                    dbg!(err);
-                }
+        .filter_map(|x| x.get(0))
+        .map(|x| x.as_str())
+        .collect();
+    let guild_id = msg.guild_id?;
+
+    let blacklist = regex_filters.blacklist();
+    let mut matches = blacklist.find_all(guild_id, &links);
+    let mut redirected_from = Vec::new();
+    let redirects = data.config.read().unwrap().redirects.clone();
+    if matches.is_empty()
+        && let Some(redirects) = redirects
+    {
+        for (link, destination) in follow_redirects(data, &links, &redirects).await {
+            let found = blacklist.find_all(guild_id, &[destination.as_str()]);
+            if !found.is_empty() {
+                redirected_from.push(link);
+                matches.extend(found);
             }
+        }
+    }
+    if matches.is_empty() {
+        return None;
+    }
+    let rules: Vec<String> = matches
+        .iter()
+        .map(|m| m.rule.regex.as_str().to_string())
+        .collect();
+
+    let bad_links = code_list(matches.iter().map(|m| m.matched.as_str()));
+    let mut embed = CreateEmbed::new()
+        .author(
+            CreateEmbedAuthor::new(&msg.author.name)
+                .icon_url(msg.author.avatar_url().unwrap_or_default()),
+        )
+        .colour(NEGATIVE_COLOR_HEX)
+        .title("Message Filtered")
+        .description(format!(
+            "Message sent by <@{}> deleted in <#{}>\n```\n{}\n```",
+            msg.author.id,
+            msg.channel_id,
+            &msg.content_safe(&ctx.cache).replace("`", "\\`")
+        ))
+        .field("Reason", format!("Bad Link: {bad_links}"), true)
+        .field(
+            if rules.len() == 1 { "Rule" } else { "Rules" },
+            code_list(rules.iter().map(String::as_str)),
+            true,
+        )
+        .timestamp(Timestamp::now())
+        .footer(CreateEmbedFooter::new(format!("ID: {}", msg.author.id)));
+    if !redirected_from.is_empty() {
+        embed = embed.field(
+            "Redirected From",
+            code_list(redirected_from.iter().map(|link| link.trim())),
+            false,
+        );
+    }
+
+    Some(FilterVerdict {
+        reason: "blacklisted link",
+        rules,
+        copies: Vec::new(),
+        embed,
+    })
+}
+
+/// Where the message's links redirect to, for the ones that redirect.
+async fn follow_redirects<'a>(
+    data: &Data,
+    links: &[&'a str],
+    redirects: &Redirects,
+) -> Vec<(&'a str, String)> {
+    let follows = links.iter().take(redirects.max_links).map(async |link| {
+        data.redirects
+            .resolve(link.trim(), redirects)
+            .await
+            .map(|destination| (*link, destination))
+    });
+
+    serenity::futures::future::join_all(follows)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Formats each item as inline code on its own line, cut short to fit in an embed field.
+fn code_list<'a>(items: impl Iterator<Item = &'a str>) -> String {
+    const FIELD_LIMIT: usize = 1024;
+
+    let mut list = String::new();
+    for item in items {
+        let line = format!("`{}`", item.replace('`', "\\`"));
+        if list.len() + line.len() + "\n…".len() > FIELD_LIMIT {
+            list.push_str("\n…");
             break;
         }
+        if !list.is_empty() {
+            list.push('\n');
+        }
+        list.push_str(&line);
     }
-    Ok(())
+    list
 }
 
-async fn image_spambot_filter(
+/// Flags messages with several images and nothing else, which spambots like to send.
+fn image_spambot_filter<'a>(
     ctx: &Context,
-    data: &Data,
-    msg: &Message,
+    msg: &'a Message,
     msg_attachments_str: Option<String>,
-) {
+) -> Option<FilterVerdict<'a>> {
     const IMAGE_COUNT_TRIGGER: i32 = 3;
     let mut image_count = 0;
     let mut not_image = 0;
@@ -163,65 +394,55 @@ async fn image_spambot_filter(
             }
         }
     }
-    if image_count >= IMAGE_COUNT_TRIGGER && not_image == 0 {
-        let mentions = CreateAllowedMentions::new()
-            .everyone(false)
-            .all_roles(false)
-            .all_users(false);
-        let _ = msg.delete(&ctx.http, None).await;
-        if let Some(blacklist_logs_channel) = data
-            .config
-            .logs
-            .mothy_blacklist_logs_channel
-            .get(&msg.guild_id.unwrap_or_default())
-        {
-            let message_content_format = if !msg.content.is_empty() {
-                format!(
-                    "```\n{}\n```",
-                    &msg.content_safe(&ctx.cache).replace("`", "\\`")
-                )
-            } else {
-                "(No message content)".to_string()
-            };
-            let embed = CreateEmbed::new()
-                .author(
-                    CreateEmbedAuthor::new(&msg.author.name)
-                        .icon_url(msg.author.avatar_url().unwrap_or_default()),
-                )
-                .colour(NEGATIVE_COLOR_HEX)
-                .title("Message Filtered")
-                .description(format!(
-                    "Message sent by <@{}> deleted in <#{}>\n{}",
-                    msg.author.id,
-                    msg.channel_id,
-                    message_content_format
-                ))
-                .field(
-                    "Message Attachments",
-                    msg_attachments_str.unwrap_or_default(),
-                    false,
-                )
-                .field(
-                    "Reason",
-                    "Possible Image Spambot Detected".to_string(),
-                    true,
-                )
-                .field(
-                    "Rule",
-                    format!("Users without filter bypass roles or moderator permissions must not send more than {} images in a single message", IMAGE_COUNT_TRIGGER),
-                    true,
-                )
-                .timestamp(Timestamp::now())
-                .footer(CreateEmbedFooter::new(format!("ID: {}", msg.author.id)));
-
-            let _ = blacklist_logs_channel
-                .send_message(
-                    &ctx.http,
-                    CreateMessage::new().embed(embed).allowed_mentions(mentions),
-                )
-                .await;
-        }
+    if image_count < IMAGE_COUNT_TRIGGER || not_image != 0 {
+        return None;
     }
+
+    let message_content_format = if !msg.content.is_empty() {
+        format!(
+            "```\n{}\n```",
+            &msg.content_safe(&ctx.cache).replace("`", "\\`")
+        )
+    } else {
+        "(No message content)".to_string()
+    };
+    let embed = CreateEmbed::new()
+        .author(
+            CreateEmbedAuthor::new(&msg.author.name)
+                .icon_url(msg.author.avatar_url().unwrap_or_default()),
+        )
+        .colour(NEGATIVE_COLOR_HEX)
+        .title("Message Filtered")
+        .description(format!(
+            "Message sent by <@{}> deleted in <#{}>\n{}",
+            msg.author.id,
+            msg.channel_id,
+            message_content_format
+        ))
+        .field(
+            "Message Attachments",
+            msg_attachments_str.unwrap_or_default(),
+            false,
+        )
+        .field(
+            "Reason",
+            "Possible Image Spambot Detected".to_string(),
+            true,
+        )
+        .field(
+            "Rule",
+            format!("Users without filter bypass roles or moderator permissions must not send more than {} images in a single message", IMAGE_COUNT_TRIGGER),
+            true,
+        )
+        .timestamp(Timestamp::now())
+        .footer(CreateEmbedFooter::new(format!("ID: {}", msg.author.id)));
+
+    Some(FilterVerdict {
+        reason: "image spam",
+        rules: Vec::new(),
+        copies: Vec::new(),
+        embed,
+    })
 }
 
 pub async fn on_message_delete(
@@ -231,36 +452,44 @@ pub async fn on_message_delete(
     guild_id: Option<GuildId>,
     data: Arc<Data>,
 ) -> Result<(), Error> {
-    let guild_name = get_guild_name_override(ctx, &data, guild_id);
-
-    let channel_name = get_channel_name(ctx, guild_id, channel_id).await;
-
     // This works but might not be optimal.
     let message = ctx
         .cache
         .message(channel_id, deleted_message_id)
         .map(|message_ref| message_ref.clone());
 
-    if let Some(message) = message {
-        let user_name = message.author.tag();
-        let content = message.content.clone();
+    let author_id = message.as_ref().map(|message| message.author.id);
+    if !should_log(ctx, &data, guild_id, Some(channel_id), author_id) {
+        return Ok(());
+    }
+
+    let guild_name = get_guild_name_override(ctx, &data, guild_id);
 
+    let channel_name = get_channel_name(ctx, guild_id, channel_id).await;
+
+    if let Some(message) = message {
         let (attachments_fmt, embeds_fmt) = attachments_embed_fmt(&message);
 
-        println!(
-            "{HI_RED}{DIM}[{}] [#{}] A message from {RESET}{}{HI_RED}{DIM} was deleted: \
-             {}{}{}{RESET}",
-            guild_name,
-            channel_name,
-            user_name,
-            content,
-            attachments_fmt.as_deref().unwrap_or(""),
-            embeds_fmt.as_deref().unwrap_or("")
+        tracing::info!(
+            event = "message_delete",
+            guild_id = guild_id.map(GuildId::get),
+            channel_id = channel_id.get(),
+            user_id = message.author.id.get(),
+            message_id = deleted_message_id.get(),
+            guild = %guild_name,
+            channel = %channel_name,
+            author = %message.author.tag(),
+            attachments = attachments_fmt.as_deref(),
+            embeds = embeds_fmt.as_deref(),
+            "{}",
+            message.content
         );
     } else {
-        println!(
-            "{HI_RED}{DIM}A message (ID:{deleted_message_id}) was deleted but was not in \
-             cache{RESET}"
+        tracing::info!(
+            guild_id = guild_id.map(GuildId::get),
+            channel_id = channel_id.get(),
+            message_id = deleted_message_id.get(),
+            "A message was deleted but was not in cache"
         );
     }
     Ok(())
@@ -294,16 +523,17 @@ pub fn attachments_embed_fmt(new_message: &Message) -> (Option<String>, Option<S
     (attachments_fmt, embeds_fmt)
 }
 
+/// The colour of the author's highest coloured role, 0 if they have none.
 #[must_use]
-pub fn author_string(ctx: &Context, msg: &Message) -> String {
+pub fn author_colour(ctx: &Context, msg: &Message) -> u32 {
     // No member meaning no roles.
     let Some(member) = &msg.member else {
-        return msg.author.tag();
+        return 0;
     };
 
-    let username = msg.author.tag();
-
-    let guild = msg.guild(&ctx.cache).unwrap();
+    let Some(guild) = msg.guild(&ctx.cache) else {
+        return 0;
+    };
 
     let mut highest: Option<&Role> = None;
 
@@ -326,13 +556,5 @@ pub fn author_string(ctx: &Context, msg: &Message) -> String {
         }
     }
 
-    let mut prefix = String::new();
-    if let Some(hr) = highest {
-        let c = hr.colour;
-        if hr.colour.0 != 0 {
-            write!(prefix, "\x1B[38;2;{};{};{}m", c.r(), c.g(), c.b()).unwrap();
-        }
-    }
-
-    format!("{prefix}{username}{RESET}")
+    highest.map_or(0, |role| role.colour.0)
 }
@@ -0,0 +1,97 @@
+use mothy_core::{
+    database_models::{FilterEscalation, FilterPunishment, GuildSettings},
+    structs::Data,
+};
+use serenity::all::{Context, EditMember, Message, Timestamp};
+
+/// Records a strike against the author of a filtered message and punishes them according to the
+/// guild's escalation ladder, returning what was done for the log embed.
+///
+/// Returns `None` without recording anything if the guild doesn't punish filtered messages.
+pub(super) async fn punish(
+    ctx: &Context,
+    data: &Data,
+    guild_settings: &GuildSettings,
+    msg: &Message,
+    reason: &str,
+) -> Option<String> {
+    let settings = &guild_settings.filter_settings;
+    if settings.timeout_minutes == 0 && settings.escalation == FilterEscalation::None {
+        return None;
+    }
+    let guild_id = msg.guild_id?;
+    let user_id = msg.author.id;
+
+    let strikes = match data
+        .database
+        .add_filter_strike(guild_id, user_id, settings.strike_window_minutes)
+        .await
+    {
+        Ok(strikes) => strikes,
+        Err(e) => {
+            tracing::error!(
+                guild_id = guild_id.get(),
+                user_id = user_id.get(),
+                error = ?e,
+                "Failed to record filter strike"
+            );
+            return Some("None, the strike couldn't be recorded".to_string());
+        }
+    };
+
+    let audit_log_reason = format!("Filtered message: {reason}");
+    let (action, result) = match settings.punishment(strikes) {
+        FilterPunishment::None => return Some(format!("None (strike {strikes})")),
+        FilterPunishment::Timeout(minutes) => {
+            let until = Timestamp::from_unix_timestamp(
+                Timestamp::now().unix_timestamp() + i64::from(minutes) * 60,
+            )
+            .unwrap_or_else(|_| Timestamp::now());
+            let builder = EditMember::new()
+                .disable_communication_until(until)
+                .audit_log_reason(&audit_log_reason);
+            (
+                format!("Timed out for {minutes} minutes"),
+                guild_id
+                    .edit_member(&ctx.http, user_id, builder)
+                    .await
+                    .map(|_| ()),
+            )
+        }
+        FilterPunishment::Kick => (
+            "Kicked".to_string(),
+            guild_id
+                .kick(&ctx.http, user_id, Some(&audit_log_reason))
+                .await,
+        ),
+        FilterPunishment::Ban => (
+            "Banned".to_string(),
+            guild_id
+                .ban(&ctx.http, user_id, 0, Some(&audit_log_reason))
+                .await,
+        ),
+    };
+
+    match result {
+        Ok(()) => {
+            tracing::info!(
+                guild_id = guild_id.get(),
+                user_id = user_id.get(),
+                author = %msg.author.tag(),
+                strikes,
+                "{action} for a filtered message"
+            );
+            Some(format!("{action} (strike {strikes})"))
+        }
+        Err(e) => {
+            tracing::warn!(
+                guild_id = guild_id.get(),
+                user_id = user_id.get(),
+                action = %action,
+                error = %e,
+                "Failed to punish filtered message author"
+            );
+            Some(format!("{action} failed (strike {strikes}): {e}"))
+        }
+    }
+}
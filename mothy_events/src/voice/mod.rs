@@ -19,19 +19,24 @@ pub async fn voice_state_update(
             if old_voice_state.channel_id != Some(new_channel_id) {
                 handle_switch(old_voice_state, new)
             } else {
-                dbg!("misc stuff");
                 // handle_misc()
                 return Ok(());
             }
         }
     };
 
-    if let Some(logs_channel) = data
-        .config
-        .logs
-        .mothy_voice_logs_channel
-        .get(&new.guild_id.unwrap_or_default())
-    {
+    let Some(guild_id) = new.guild_id else {
+        return Ok(());
+    };
+
+    let guild_settings = data
+        .database
+        .guild_handler
+        .get(guild_id)
+        .await
+        .map_err(Error::from_anyhow)?;
+
+    if let Some(logs_channel) = guild_settings.log_settings.voice_logs_channel_id {
         logs_channel
             .send_message(&ctx.http, CreateMessage::new().embed(embed))
             .await?;
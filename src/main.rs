@@ -11,6 +11,7 @@ pub use mothy_core::structs::Command;
 #[tokio::main]
 async fn main() {
     let _ = dotenvy::dotenv();
+    let _log_guard = mothy_core::logging::init();
 
     let options = poise::FrameworkOptions {
         commands: mothy_commands::commands(),
@@ -25,6 +26,9 @@ async fn main() {
             ..Default::default()
         },
 
+        command_check: Some(|ctx| Box::pin(mothy_commands::checks::not_banned(ctx))),
+        post_command: |ctx| Box::pin(mothy_commands::audit::post_command(ctx)),
+        on_error: |error| Box::pin(mothy_commands::audit::on_error(error)),
         skip_checks_for_owners: false,
         ..Default::default()
     };
@@ -41,16 +45,22 @@ async fn main() {
     let mut http = serenity::Http::new(token.clone());
     http.default_allowed_mentions = Some(serenity::CreateAllowedMentions::new());
 
+    let database = mothy_core::database::Database::init().await;
+    let regex_filters = mothy_core::regex_filters::init(&database).await;
+
     let client = serenity::ClientBuilder::new_with_http(token, Arc::new(http), intents)
         .framework(framework)
         .event_handler(mothy_events::Handler)
         .data(Arc::new(mothy_core::structs::Data {
             time_started: std::time::Instant::now(),
             has_started: AtomicBool::new(false),
-            database: mothy_core::database::Database::init().await,
+            database,
             james_scores: mothy_core::score_data::init().unwrap_or_default(),
-            regex_filters: mothy_core::regex_filters::init(),
-            config: mothy_core::structs::MothyConfig::new(),
+            regex_filters,
+            redirects: mothy_core::redirects::RedirectFollower::new(
+                mothy_core::redirects::HttpResolver::new(),
+            ),
+            config: std::sync::RwLock::new(mothy_core::structs::MothyConfig::new()),
             command_data: mothy_commands::init_data(),
             moth_data: moth_data::moth_data_init().unwrap_or_default(),
         }))